@@ -0,0 +1,40 @@
+//! 离线训练 1v1 CFR 策略表
+//!
+//! 用法：cargo run --release --example train_cfr -- <输出文件> [每人骰子数] [迭代次数] [随机种子]
+//!
+//! 未给种子时随机选取并打印，用同一种子重跑可得到相同的策略表
+
+use liar_dice_lib::game::cfr;
+use liar_dice_lib::game::types::GameConfig;
+
+fn main() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let output = args
+        .next()
+        .ok_or("usage: train_cfr <output> [dice per player] [iterations] [seed]")?;
+    let dice_per_player = match args.next() {
+        Some(arg) => arg.parse().map_err(|_| "Invalid dice count")?,
+        None => 2,
+    };
+    let iterations = match args.next() {
+        Some(arg) => arg.parse().map_err(|_| "Invalid iteration count")?,
+        None => 50_000,
+    };
+    let seed = match args.next() {
+        Some(arg) => arg.parse().map_err(|_| "Invalid seed")?,
+        None => rand::random(),
+    };
+
+    let config = GameConfig {
+        dice_per_player,
+        reroll_straights: false,
+        ..GameConfig::default()
+    };
+    let table = cfr::train(&config, iterations, seed)?;
+    table.save(&output)?;
+    println!(
+        "Trained {} iterations per subgame for {} dice with seed {}, wrote {}",
+        iterations, dice_per_player, seed, output
+    );
+    Ok(())
+}
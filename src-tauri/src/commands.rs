@@ -1,5 +1,9 @@
 use crate::game::engine::GameEngine;
-use crate::game::types::GameView;
+use crate::game::odds::{Odds, OddsQuery};
+use crate::game::personality::{personalities, PersonalityInfo};
+use crate::game::profile::PlayerProfile;
+use crate::game::report::MatchReport;
+use crate::game::types::{GameConfig, GameView, Hint};
 use std::sync::Mutex;
 use tauri::State;
 
@@ -7,32 +11,89 @@ pub struct AppState {
     pub engine: Mutex<GameEngine>,
 }
 
+/// 按配置开始新的一场，AI 难度由 `config.difficulty` 决定；未给配置时用默认规则
 #[tauri::command]
-pub fn start_game(state: State<'_, AppState>) -> Result<GameView, String> {
+pub fn start_game(
+    state: State<'_, AppState>,
+    config: Option<GameConfig>,
+) -> Result<GameView, String> {
     let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
-    Ok(engine.start_game())
+    engine.start_game(config.unwrap_or_default())
 }
 
 #[tauri::command]
-pub fn player_bid(state: State<'_, AppState>, count: u32, face: u32) -> Result<GameView, String> {
+pub fn player_bid(
+    state: State<'_, AppState>,
+    seat: usize,
+    count: u32,
+    face: u32,
+    pure: Option<bool>,
+) -> Result<GameView, String> {
     let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
-    engine.player_bid(count, face)
+    engine.player_bid(seat, count, face, pure.unwrap_or(false))
 }
 
 #[tauri::command]
-pub fn player_challenge(state: State<'_, AppState>) -> Result<GameView, String> {
+pub fn player_challenge(state: State<'_, AppState>, seat: usize) -> Result<GameView, String> {
     let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
-    engine.player_challenge()
+    engine.player_challenge(seat)
 }
 
 #[tauri::command]
-pub fn get_game_state(state: State<'_, AppState>) -> Result<GameView, String> {
+pub fn player_spot_on(state: State<'_, AppState>, seat: usize) -> Result<GameView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    engine.player_spot_on(seat)
+}
+
+#[tauri::command]
+pub fn get_game_state(state: State<'_, AppState>, seat: Option<usize>) -> Result<GameView, String> {
     let engine = state.engine.lock().map_err(|e| e.to_string())?;
-    Ok(engine.state.to_view())
+    let seat = seat.unwrap_or_else(|| engine.default_seat());
+    if seat >= engine.state.seats.len() {
+        return Err("No such seat".to_string());
+    }
+    Ok(engine.view(seat))
 }
 
+/// 轮到该人类座位时的提示：当前叫数的概率、最有把握的加注与推荐行动
 #[tauri::command]
-pub fn next_round(state: State<'_, AppState>) -> Result<GameView, String> {
+pub fn get_hint(state: State<'_, AppState>, seat: Option<usize>) -> Result<Hint, String> {
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let seat = seat.unwrap_or_else(|| engine.default_seat());
+    engine.hint(seat)
+}
+
+#[tauri::command]
+pub fn next_round(state: State<'_, AppState>, seat: usize) -> Result<GameView, String> {
     let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
-    engine.next_round()
+    engine.next_round(seat)
+}
+
+/// 整场结束后的复盘：各局决策、诈叫率、开的准确率、最冒险的叫数与运气
+#[tauri::command]
+pub fn get_match_report(state: State<'_, AppState>) -> Result<MatchReport, String> {
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    engine.match_report()
+}
+
+/// 某名人类玩家的历史档案，尚无记录时为空
+#[tauri::command]
+pub fn get_profile(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Option<PlayerProfile>, String> {
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    Ok(engine.profile(&name).cloned())
+}
+
+/// 可为 AI 座位选择的性格
+#[tauri::command]
+pub fn list_personalities() -> Vec<PersonalityInfo> {
+    personalities()
+}
+
+/// 叫数成立的概率：给定自己的骰子、各对手的骰子数与规则，不依赖当前对局
+#[tauri::command]
+pub fn calculate_odds(query: OddsQuery) -> Result<Odds, String> {
+    query.odds()
 }
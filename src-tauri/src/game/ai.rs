@@ -1,49 +1,235 @@
+use crate::game::inference::{opponent_posteriors, HandPosterior, DEFAULT_TRUTHFULNESS};
+use crate::game::odds::{challenge_value, convolve, matching_faces, seat_count_pmf, spot_on_value};
+use crate::game::profile::{bid_margin, PlayerProfile};
+use crate::game::strategy::Strategy;
 use crate::game::types::*;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+use std::collections::HashMap;
 
+/// 诈叫可接受的成立概率比加注下限低多少
+const BLUFF_MARGIN: f64 = 0.2;
+/// 提示中列出的加注个数
+const HINT_RAISES: usize = 3;
+
+/// 加注大小的偏好
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RaiseSizing {
+    /// 成立概率最高，并偏向自己手中多的点数
+    Steady,
+    /// 在加注下限之上尽量叫大，给下家施压
+    Jump,
+    /// 只看成立概率
+    Probability,
+}
+
+/// 基于概率估计的启发式 AI，按难度调整门槛、失误与诈叫
 pub struct AiEngine {
     /// 开的概率阈值：低于此值 AI 会选择开
     challenge_threshold: f64,
+    /// 是否会选择定
+    calls_spot_on: bool,
+    /// 定的概率阈值：叫数恰好成立的概率不低于此值时 AI 会选择定
+    spot_on_threshold: f64,
+    /// 加注的概率下限：只考虑成立概率不低于此值的加注
+    raise_threshold: f64,
+    /// 每次决策失误（无视计算直接开或机械加一）的概率
+    mistake_rate: f64,
+    /// 加注时故意叫自己手中没有的点数的概率（性格参数）
+    bluff_rate: f64,
+    /// 假定对手叫数的诚实度，用于从叫数历史推断对手手牌；`None` 为不推断
+    truthfulness: Option<f64>,
+    /// 加注前比较「开」的胜率与加注后叫数成立的概率，取更优者
+    weigh_raise_against_challenge: bool,
+    raise_sizing: RaiseSizing,
+    rng: StdRng,
+}
+
+impl Default for AiEngine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AiEngine {
     pub fn new() -> Self {
-        AiEngine {
+        Self::for_difficulty(Difficulty::default())
+    }
+
+    /// 各难度的 AI：
+    /// - Easy：门槛宽松，常犯错，从不定，不读叫数历史
+    /// - Normal：按概率稳健决策，对对手叫数半信半疑
+    /// - Hard：更早开、偶尔诈叫，较信任对手叫数
+    /// - Expert：在 Hard 基础上加注前权衡开的胜率，诈叫更频繁
+    pub fn for_difficulty(difficulty: Difficulty) -> Self {
+        let engine = AiEngine {
             challenge_threshold: 0.35,
+            calls_spot_on: true,
+            spot_on_threshold: 0.45,
+            raise_threshold: 0.45,
+            mistake_rate: 0.0,
+            bluff_rate: 0.0,
+            truthfulness: Some(0.4),
+            weigh_raise_against_challenge: false,
+            raise_sizing: RaiseSizing::Steady,
+            rng: StdRng::from_entropy(),
+        };
+        match difficulty {
+            Difficulty::Easy => AiEngine {
+                challenge_threshold: 0.2,
+                calls_spot_on: false,
+                raise_threshold: 0.3,
+                mistake_rate: 0.2,
+                truthfulness: None,
+                ..engine
+            },
+            Difficulty::Normal => engine,
+            Difficulty::Hard => AiEngine {
+                challenge_threshold: 0.4,
+                raise_threshold: 0.5,
+                bluff_rate: 0.1,
+                truthfulness: Some(DEFAULT_TRUTHFULNESS),
+                ..engine
+            },
+            // Master 由蒙特卡洛 AI 担任，作为启发式时与 Expert 相同
+            Difficulty::Expert | Difficulty::Master => AiEngine {
+                challenge_threshold: 0.4,
+                spot_on_threshold: 0.4,
+                raise_threshold: 0.5,
+                bluff_rate: 0.15,
+                truthfulness: Some(DEFAULT_TRUTHFULNESS),
+                weigh_raise_against_challenge: true,
+                ..engine
+            },
         }
     }
 
-    /// AI 决策：叫数或开
-    pub fn decide(&self, state: &GameState) -> Action {
-        let current_bid = match &state.current_bid {
-            Some(bid) => bid,
-            None => {
-                // 没有当前叫数，AI 先叫一个保守的数
-                return Action::Bid(self.make_initial_bid(state));
-            }
-        };
+    /// 设置诈叫倾向（性格参数），覆盖难度的默认值
+    pub fn with_bluff_rate(self, bluff_rate: f64) -> Self {
+        AiEngine {
+            bluff_rate: bluff_rate.clamp(0.0, 1.0),
+            ..self
+        }
+    }
 
-        // 计算当前叫数成立的概率
-        let prob = self.calculate_probability(state, current_bid);
+    /// 按性格覆盖门槛、诈叫倾向与加注大小；失误率与读叫数的方式仍由难度决定
+    pub fn with_personality(self, personality: Personality) -> Self {
+        match personality {
+            Personality::Cautious => AiEngine {
+                challenge_threshold: 0.45,
+                spot_on_threshold: 0.5,
+                raise_threshold: 0.6,
+                bluff_rate: 0.0,
+                raise_sizing: RaiseSizing::Steady,
+                ..self
+            },
+            Personality::Reckless => AiEngine {
+                challenge_threshold: 0.2,
+                raise_threshold: 0.3,
+                bluff_rate: 0.3,
+                raise_sizing: RaiseSizing::Jump,
+                ..self
+            },
+            Personality::Mathematician => AiEngine {
+                challenge_threshold: 0.4,
+                spot_on_threshold: 0.4,
+                raise_threshold: 0.5,
+                bluff_rate: 0.05,
+                weigh_raise_against_challenge: true,
+                raise_sizing: RaiseSizing::Probability,
+                ..self
+            },
+            Personality::Bluffer => AiEngine {
+                challenge_threshold: 0.35,
+                raise_threshold: 0.45,
+                bluff_rate: 0.5,
+                raise_sizing: RaiseSizing::Steady,
+                ..self
+            },
+        }
+    }
 
-        if prob < self.challenge_threshold {
-            // 概率太低，开
+    /// 固定失误与诈叫所用的随机种子，使 AI 行为可重放
+    pub fn with_seed(self, seed: u64) -> Self {
+        AiEngine {
+            rng: StdRng::seed_from_u64(seed),
+            ..self
+        }
+    }
+
+    /// 为 `observation` 的座位给出提示：当前叫数的概率、最有把握的加注与推荐行动。
+    /// 推荐行动即本 AI 在该位置的决策，提示用的 AI 应当不诈叫、不失误
+    pub fn hint(&mut self, observation: &PlayerObservation) -> Hint {
+        let belief = self.belief(observation);
+        let current = observation.current_bid.as_ref();
+
+        // 同一点数与叫法下，最小的合法加注成立概率最高
+        let mut raises: Vec<RaiseOdds> = (1..=observation.config.die_sides)
+            .flat_map(|face| {
+                bid_modes(observation.wilds_active(), face)
+                    .into_iter()
+                    .map(move |pure| (face, pure))
+            })
+            .filter_map(|(face, pure)| {
+                (1..=observation.total_dice())
+                    .find_map(|count| observation.validate_bid(Bid { count, face, pure }).ok())
+            })
+            .map(|bid| RaiseOdds {
+                probability: belief.probability(&bid),
+                bid,
+            })
+            .collect();
+        raises.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+        raises.truncate(HINT_RAISES);
+
+        Hint {
+            bid_probability: current.map(|bid| belief.probability(bid)),
+            exact_probability: current.map(|bid| belief.exact_probability(bid)),
+            raises,
+            recommended: self.decide(observation),
+        }
+    }
+
+    /// 失误：不看概率，要么直接开，要么同点数机械地加一
+    fn blunder(&mut self, observation: &PlayerObservation, current: &Bid) -> Action {
+        let raise = Bid {
+            count: current.count + 1,
+            ..current.clone()
+        };
+        if raise.count <= observation.total_dice() && self.rng.gen_bool(0.5) {
+            Action::Bid(raise)
+        } else {
             Action::Challenge
+        }
+    }
+
+    /// 放弃加注时的叫法：不会定的 AI 总是开
+    fn call(&self, prob: f64, exact: f64) -> Action {
+        if self.calls_spot_on {
+            Self::best_call(prob, exact)
         } else {
-            // 尝试加注
-            match self.find_raise(state, current_bid) {
-                Some(bid) => Action::Bid(bid),
-                None => Action::Challenge, // 无法合理加注，开
-            }
+            Action::Challenge
         }
     }
 
-    /// 初始叫数：选择自己手中最多的点数
-    fn make_initial_bid(&self, state: &GameState) -> Bid {
+    /// 比较开与定的期望胜场
+    fn best_call(prob: f64, exact: f64) -> Action {
+        if spot_on_value(exact) > challenge_value(prob) {
+            Action::SpotOn
+        } else {
+            Action::Challenge
+        }
+    }
+
+    /// 初始叫数：选择自己手中最多的点数（百搭规则下 1 点计入其他点数）
+    fn make_initial_bid(&self, observation: &PlayerObservation) -> Bid {
         let mut best_face = 1;
         let mut best_count = 0;
 
-        for face in 1..=6 {
-            let count = state.ai_dice.iter().filter(|&&d| d == face).count();
+        for face in 1..=observation.config.die_sides {
+            let count = observation.my_count(face, false);
             if count > best_count {
                 best_count = count;
                 best_face = face;
@@ -52,51 +238,153 @@ impl AiEngine {
 
         // 保守地叫自己拥有的数量（假设对手也可能有一些）
         Bid {
-            count: best_count as u32,
-            face: best_face as u32,
+            count: best_count,
+            face: best_face,
+            pure: observation.wilds_active() && best_face == WILD_FACE,
         }
     }
 
-    /// 寻找合理的加注
-    fn find_raise(&self, state: &GameState, current_bid: &Bid) -> Option<Bid> {
-        // 策略：尝试叫自己手中最多的点数
-        let mut candidates: Vec<(Bid, f64)> = Vec::new();
+    /// 寻找合理的加注，返回加注及其成立概率。
+    /// 按 `bluff_rate` 的概率改为诈叫：叫自己手中没有的点数，
+    /// 但不向有档案的下家叫超出其惯常开的幅度的诈叫
+    fn find_raise(&mut self, belief: &Belief) -> Option<(Bid, f64)> {
+        let observation = belief.observation;
+        let bluffing = self.rng.gen_bool(self.bluff_rate);
+        // 下家习惯在叫数超出期望多少时开：超过这个幅度的诈叫多半会被开
+        let challenge_margin = observation
+            .next_opponent()
+            .and_then(|seat| observation.profile(seat))
+            .and_then(PlayerProfile::challenge_margin);
+        let mut candidates: Vec<(Bid, f64, f64)> = Vec::new();
+        let mut bluffs: Vec<(Bid, f64)> = Vec::new();
 
-        for face in 1..=6u32 {
-            let my_count = state.ai_dice.iter().filter(|&&d| d == face).count() as u32;
+        for face in 1..=observation.config.die_sides {
+            for pure in bid_modes(observation.wilds_active(), face) {
+                let my_count = observation.my_count(face, pure);
 
-            // 尝试不同数量
-            for count in 1..=(state.human_dice_count + state.ai_dice_count) {
-                let bid = Bid { count, face };
+                // 尝试不同数量
+                for count in 1..=observation.total_dice() {
+                    let bid = Bid { count, face, pure };
 
-                // 必须是合法加注
-                if !current_bid.is_valid_raise(&bid) {
-                    continue;
-                }
+                    // 必须是本局合法的加注（独骰局锁定点数）
+                    if observation.validate_bid(bid.clone()).is_err() {
+                        continue;
+                    }
 
-                let prob = self.calculate_probability(state, &bid);
-                if prob >= 0.45 {
-                    // 倾向于叫自己有的点数
-                    let bonus = if my_count > 0 {
-                        my_count as f64 * 0.1
-                    } else {
-                        0.0
-                    };
-                    candidates.push((bid, prob + bonus));
+                    let prob = belief.probability(&bid);
+                    let share = matching_faces(observation.wilds_active(), &bid) as f64
+                        / observation.config.die_sides as f64;
+                    let margin = bid_margin(&bid, 0, observation.total_dice(), share);
+                    if my_count == 0
+                        && prob >= self.raise_threshold - BLUFF_MARGIN
+                        && challenge_margin.is_none_or(|limit| margin <= limit)
+                    {
+                        bluffs.push((bid.clone(), prob));
+                    }
+                    if prob >= self.raise_threshold {
+                        let score = match self.raise_sizing {
+                            // 倾向于叫自己有的点数
+                            RaiseSizing::Steady => prob + my_count as f64 * 0.1,
+                            RaiseSizing::Jump => count as f64 + prob,
+                            RaiseSizing::Probability => prob,
+                        };
+                        candidates.push((bid, prob, score));
+                    }
                 }
             }
         }
 
-        // 选择概率最高的候选
-        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        candidates.into_iter().next().map(|(bid, _)| bid)
+        if bluffing {
+            if let Some(bluff) = self.pick_bluff(bluffs) {
+                return Some(bluff);
+            }
+        }
+
+        // 选择得分最高的候选
+        candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+        candidates
+            .into_iter()
+            .next()
+            .map(|(bid, prob, _)| (bid, prob))
+    }
+
+    /// 诈叫的大小按混合策略随机选取：各候选被选中的概率与其成立概率成正比。
+    /// 大叫数的诈叫更少见但不会绝迹，对手无法单凭叫数大小识破诈叫
+    fn pick_bluff(&mut self, bluffs: Vec<(Bid, f64)>) -> Option<(Bid, f64)> {
+        let weights = WeightedIndex::new(bluffs.iter().map(|(_, prob)| *prob)).ok()?;
+        let pick = weights.sample(&mut self.rng);
+        bluffs.into_iter().nth(pick)
+    }
+
+    /// 观察者视角下的场面估计：叫过数的对手按叫数历史推断手牌
+    fn belief<'a>(&self, observation: &'a PlayerObservation) -> Belief<'a> {
+        Belief {
+            observation,
+            posteriors: match self.truthfulness {
+                Some(truthfulness) => opponent_posteriors(observation, truthfulness),
+                None => Vec::new(),
+            },
+            distributions: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl Strategy for AiEngine {
+    /// AI 决策：叫数、开或定
+    fn decide(&mut self, observation: &PlayerObservation) -> Action {
+        let current_bid = match &observation.current_bid {
+            Some(bid) => bid,
+            None => {
+                // 没有当前叫数，AI 先叫一个保守的数
+                return Action::Bid(self.make_initial_bid(observation));
+            }
+        };
+
+        if self.rng.gen_bool(self.mistake_rate) {
+            return self.blunder(observation, current_bid);
+        }
+
+        // 计算当前叫数成立的概率，以及恰好成立的概率
+        let belief = self.belief(observation);
+        let prob = belief.probability(current_bid);
+        let exact = belief.exact_probability(current_bid);
+
+        if prob < self.challenge_threshold {
+            // 概率太低，开或定取期望更高者
+            self.call(prob, exact)
+        } else if self.calls_spot_on && exact >= self.spot_on_threshold {
+            // 很可能恰好成立，定
+            Action::SpotOn
+        } else {
+            // 尝试加注
+            match self.find_raise(&belief) {
+                Some((_, raise_prob))
+                    if self.weigh_raise_against_challenge && raise_prob < 1.0 - prob =>
+                {
+                    // 开的胜率高于加注后叫数成立的概率
+                    self.call(prob, exact)
+                }
+                Some((bid, _)) => Action::Bid(bid),
+                None => self.call(prob, exact), // 无法合理加注，开或定
+            }
+        }
     }
+}
 
-    /// 计算「至少 count 个 face 点」的概率
-    /// AI 知道自己的骰子，对手的骰子视为均匀分布
-    fn calculate_probability(&self, state: &GameState, bid: &Bid) -> f64 {
-        // AI 已知自己有多少个 bid.face
-        let my_count = state.ai_dice.iter().filter(|&&d| d == bid.face).count() as u32;
+/// 某座位对场上骰子的估计：自己的骰子已知；对手的骰子按掷骰规则（含顺子重掷）
+/// 的精确分布估计，叫过数的对手改用推断出的后验
+struct Belief<'a> {
+    observation: &'a PlayerObservation,
+    posteriors: Vec<(usize, HandPosterior)>,
+    /// 按 (点数, 是否斋) 缓存的对手分布，同一次决策会反复查询
+    distributions: RefCell<HashMap<(u32, bool), Vec<f64>>>,
+}
+
+impl Belief<'_> {
+    /// 「至少 count 个 face 点」的概率
+    fn probability(&self, bid: &Bid) -> f64 {
+        // 已知自己有多少个 bid.face（含百搭）
+        let my_count = self.my_count(bid);
 
         // 还需要从对手的骰子中凑多少个
         let needed = if bid.count > my_count {
@@ -105,97 +393,590 @@ impl AiEngine {
             return 1.0; // 自己就够了，100% 成立
         };
 
-        let opponent_dice = state.human_dice_count;
-        if needed > opponent_dice {
-            return 0.0; // 不可能，对手骰子不够
-        }
+        // P(X >= needed)，X 为对手骰子中计入叫数的个数；对手骰子不够时为 0
+        self.with_distribution(bid, |dist| dist.iter().skip(needed as usize).sum())
+    }
 
-        // 二项分布：P(X >= needed)，X ~ Binomial(opponent_dice, 1/6)
-        let p = 1.0 / 6.0;
-        let mut prob = 0.0;
-        for k in needed..=opponent_dice {
-            prob += binomial_pmf(opponent_dice, k, p);
+    /// 「恰好 count 个 face 点」的概率
+    fn exact_probability(&self, bid: &Bid) -> f64 {
+        let my_count = self.my_count(bid);
+        if my_count > bid.count {
+            return 0.0; // 自己的骰子已经超过叫数
         }
-        prob
+
+        let needed = bid.count - my_count;
+        self.with_distribution(bid, |dist| {
+            dist.get(needed as usize).copied().unwrap_or(0.0)
+        })
     }
-}
 
-/// 二项分布概率质量函数：C(n, k) * p^k * (1-p)^(n-k)
-fn binomial_pmf(n: u32, k: u32, p: f64) -> f64 {
-    let coeff = binomial_coefficient(n, k);
-    coeff * p.powi(k as i32) * (1.0 - p).powi((n - k) as i32)
-}
+    fn my_count(&self, bid: &Bid) -> u32 {
+        self.observation.my_count(bid.face, bid.pure)
+    }
 
-/// 组合数 C(n, k)
-fn binomial_coefficient(n: u32, k: u32) -> f64 {
-    if k > n {
-        return 0.0;
+    fn with_distribution<T>(&self, bid: &Bid, f: impl FnOnce(&[f64]) -> T) -> T {
+        let mut cache = self.distributions.borrow_mut();
+        let dist = cache
+            .entry((bid.face, bid.pure))
+            .or_insert_with(|| self.opponent_distribution(bid));
+        f(dist)
     }
-    let k = k.min(n - k); // 优化：C(n,k) = C(n,n-k)
-    let mut result = 1.0;
-    for i in 0..k {
-        result *= (n - i) as f64;
-        result /= (i + 1) as f64;
+
+    /// 所有对手骰子中计入 `bid` 的个数的分布，下标为个数。
+    /// 各座位独立掷骰，因此是每个座位分布的卷积
+    fn opponent_distribution(&self, bid: &Bid) -> Vec<f64> {
+        let observation = self.observation;
+        let wilds_active = observation.wilds_active();
+        let mut dist = vec![1.0];
+        for i in observation.opponents() {
+            let hand = match self.posteriors.iter().find(|(s, _)| *s == i) {
+                Some((_, posterior)) => posterior.count_pmf(bid, wilds_active),
+                None => seat_count_pmf(
+                    &observation.config,
+                    observation.seats[i].dice_count,
+                    wilds_active,
+                    bid,
+                ),
+            };
+            dist = convolve(&dist, &hand);
+        }
+        dist
+    }
+}
+
+/// 某点数可用的叫法：百搭生效时非 1 点可飞可斋，1 点只能斋
+pub fn bid_modes(wilds_active: bool, face: u32) -> Vec<bool> {
+    match (wilds_active, face == WILD_FACE) {
+        (false, _) => vec![false],
+        (true, true) => vec![true],
+        (true, false) => vec![false, true],
     }
-    result
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game::dice::SeededDice;
+    use crate::game::odds::binomial_pmf;
 
-    #[test]
-    fn test_binomial_coefficient() {
-        assert!((binomial_coefficient(5, 2) - 10.0).abs() < 1e-9);
-        assert!((binomial_coefficient(10, 3) - 120.0).abs() < 1e-9);
-        assert!((binomial_coefficient(5, 0) - 1.0).abs() < 1e-9);
-    }
+    const HUMAN: usize = 0;
+    const AI: usize = 1;
 
     #[test]
     fn test_probability_certain() {
         let ai = AiEngine::new();
-        let mut state = GameState::new();
-        state.ai_dice = vec![3, 3, 3, 3, 3];
-        state.human_dice = vec![1, 2, 4, 5, 6];
-        state.human_dice_count = 5;
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![3, 3, 3, 3, 3]);
+        observation.seats[HUMAN].dice_count = 5;
 
         // AI 有 5 个 3，叫 5 个 3 概率应该是 1.0
-        let prob = ai.calculate_probability(&state, &Bid { count: 5, face: 3 });
+        let prob = ai.belief(&observation).probability(&Bid::new(5, 3));
         assert!((prob - 1.0).abs() < 1e-9);
     }
 
     #[test]
     fn test_probability_impossible() {
         let ai = AiEngine::new();
-        let mut state = GameState::new();
-        state.ai_dice = vec![1, 2, 4, 5, 6];
-        state.human_dice_count = 3;
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![1, 2, 4, 5, 6]);
+        observation.seats[HUMAN].dice_count = 3;
 
         // AI 没有 3，需要对手 3 个骰子全是 3，概率很低
-        let prob = ai.calculate_probability(&state, &Bid { count: 4, face: 3 });
+        let prob = ai.belief(&observation).probability(&Bid::new(4, 3));
         assert_eq!(prob, 0.0); // 需要 4 个但对手只有 3 个骰子
     }
 
+    #[test]
+    fn test_probability_wild_ones() {
+        let ai = AiEngine::new();
+        let mut observation = PlayerObservation::new(
+            GameConfig {
+                wild_ones: true,
+                ..Default::default()
+            },
+            AI,
+            vec![1, 1, 2, 4, 5],
+        );
+        observation.seats[HUMAN].dice_count = 1;
+
+        // AI 的两个 1 点计入 3 点，还需对手 1 颗骰子是 3 或 1
+        let prob = ai.belief(&observation).probability(&Bid::new(3, 3));
+        assert!((prob - 2.0 / 6.0).abs() < 1e-9);
+
+        // 叫 1 点时不享受百搭
+        let prob = ai.belief(&observation).probability(&Bid::new_pure(3, 1));
+        assert!((prob - 1.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_probability_uses_die_sides() {
+        let ai = AiEngine::new();
+        let mut observation = PlayerObservation::new(
+            GameConfig {
+                die_sides: 8,
+                ..Default::default()
+            },
+            AI,
+            vec![8, 2, 3, 4, 5],
+        );
+        observation.seats[HUMAN].dice_count = 1;
+
+        let prob = ai.belief(&observation).probability(&Bid::new(2, 8));
+        assert!((prob - 1.0 / 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_probability_pure_bid() {
+        let ai = AiEngine::new();
+        let mut observation = PlayerObservation::new(
+            GameConfig {
+                wild_ones: true,
+                ..Default::default()
+            },
+            AI,
+            vec![1, 1, 3, 4, 5],
+        );
+        observation.seats[HUMAN].dice_count = 1;
+
+        // 斋叫时 AI 的 1 点不计入，只有 1 个 3，需要对手恰好是 3
+        let prob = ai.belief(&observation).probability(&Bid::new_pure(2, 3));
+        assert!((prob - 1.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_find_raise_respects_mode_ordering() {
+        let mut ai = AiEngine::new();
+        let mut observation = PlayerObservation::new(
+            GameConfig {
+                wild_ones: true,
+                ..Default::default()
+            },
+            AI,
+            vec![2, 2, 2, 2, 5],
+        );
+        observation.current_bid = Some(Bid::new_pure(3, 6));
+        let (bid, _) = ai.find_raise(&ai.belief(&observation)).unwrap();
+        assert!(Bid::new_pure(3, 6).is_valid_raise(&bid));
+        assert!(bid.pure || bid.count >= 7);
+    }
+
+    #[test]
+    fn test_initial_bid_counts_wilds() {
+        let ai = AiEngine::new();
+        let observation = PlayerObservation::new(
+            GameConfig {
+                wild_ones: true,
+                ..Default::default()
+            },
+            AI,
+            vec![1, 1, 4, 4, 6],
+        );
+
+        let bid = ai.make_initial_bid(&observation);
+        assert_eq!(bid.face, 4);
+        assert_eq!(bid.count, 4);
+    }
+
+    #[test]
+    fn test_palifico_probability_ignores_wilds() {
+        let ai = AiEngine::new();
+        let mut observation = PlayerObservation::new(
+            GameConfig {
+                wild_ones: true,
+                ..Default::default()
+            },
+            AI,
+            vec![1, 1, 2, 4, 5],
+        );
+        observation.palifico = Some(HUMAN);
+        observation.seats[HUMAN].dice_count = 1;
+
+        let prob = ai.belief(&observation).probability(&Bid::new(1, 3));
+        assert!((prob - 1.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_palifico_raise_keeps_locked_face() {
+        let mut ai = AiEngine::new();
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![4, 4, 4, 2, 2]);
+        observation.palifico = Some(HUMAN);
+        observation.seats[HUMAN].dice_count = 1;
+        observation.current_bid = Some(Bid::new(1, 2));
+        observation
+            .bid_history
+            .push((HUMAN, Action::Bid(Bid::new(1, 2))));
+        let (bid, _) = ai.find_raise(&ai.belief(&observation)).unwrap();
+        assert_eq!(bid.face, 2);
+    }
+
     #[test]
     fn test_initial_bid() {
         let ai = AiEngine::new();
-        let mut state = GameState::new();
-        state.ai_dice = vec![2, 2, 2, 5, 6];
+        let observation = PlayerObservation::new(GameConfig::default(), AI, vec![2, 2, 2, 5, 6]);
 
-        let bid = ai.make_initial_bid(&state);
+        let bid = ai.make_initial_bid(&observation);
         assert_eq!(bid.face, 2); // 最多的是 2
         assert_eq!(bid.count, 3); // 有 3 个
     }
 
     #[test]
     fn test_decide_challenge_unlikely_bid() {
-        let ai = AiEngine::new();
-        let mut state = GameState::new();
-        state.ai_dice = vec![1, 2, 4, 5, 6]; // 没有 3
-        state.human_dice_count = 5;
-        state.current_bid = Some(Bid { count: 8, face: 3 }); // 叫 8 个 3，不太可能
+        let mut ai = AiEngine::new();
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![1, 2, 4, 5, 6]); // 没有 3
+        observation.seats[HUMAN].dice_count = 5;
+        observation.current_bid = Some(Bid::new(8, 3)); // 叫 8 个 3，不太可能
 
-        let action = ai.decide(&state);
+        let action = ai.decide(&observation);
         assert_eq!(action, Action::Challenge);
     }
+
+    #[test]
+    fn test_exact_probability() {
+        let ai = AiEngine::new();
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![3, 3, 4, 5, 6]);
+        observation.seats[HUMAN].dice_count = 2;
+
+        // 需要对手恰好 1 个 3：C(2,1) * 1/6 * 5/6
+        let prob = ai.belief(&observation).exact_probability(&Bid::new(3, 3));
+        assert!((prob - 10.0 / 36.0).abs() < 1e-9);
+        // 自己已有 2 个，叫 1 个不可能恰好成立
+        assert_eq!(
+            ai.belief(&observation).exact_probability(&Bid::new(1, 3)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_decide_spot_on_likely_exact() {
+        let mut ai = AiEngine::new();
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![3, 3, 3, 5, 6]);
+        observation.seats[HUMAN].dice_count = 1;
+        observation.current_bid = Some(Bid::new(3, 3));
+
+        // 对手唯一的骰子不是 3 的概率为 5/6
+        let action = ai.decide(&observation);
+        assert_eq!(action, Action::SpotOn);
+    }
+
+    #[test]
+    fn test_best_call_prefers_higher_ev() {
+        assert_eq!(AiEngine::best_call(0.3, 0.05), Action::Challenge);
+        assert_eq!(AiEngine::best_call(0.5, 0.45), Action::SpotOn);
+    }
+
+    /// 用真实掷骰（含顺子重掷）估计对手骰子的命中分布
+    fn monte_carlo(config: GameConfig, ai_seat: usize, bid: &Bid, needed: u32) -> (f64, f64) {
+        let mut dice = SeededDice::new(11);
+        let mut sim = GameState::with_dice(config, &mut dice);
+        let trials = 100_000;
+        let (mut at_least, mut exact) = (0, 0);
+        for _ in 0..trials {
+            sim.roll_all_dice(&mut dice);
+            let hits: u32 = (0..sim.seats.len())
+                .filter(|&i| i != ai_seat)
+                .map(|i| sim.count_in(&sim.seats[i].dice, bid.face, bid.pure))
+                .sum();
+            at_least += (hits >= needed) as u32;
+            exact += (hits == needed) as u32;
+        }
+        (
+            at_least as f64 / trials as f64,
+            exact as f64 / trials as f64,
+        )
+    }
+
+    #[test]
+    fn test_probability_matches_monte_carlo_with_straight_reroll() {
+        let ai = AiEngine::new();
+        let observation = PlayerObservation::new(GameConfig::default(), AI, vec![2, 2, 4, 4, 6]);
+        let bid = Bid::new(2, 3);
+
+        let prob = ai.belief(&observation).probability(&bid);
+        let exact = ai.belief(&observation).exact_probability(&bid);
+        let (mc_prob, mc_exact) = monte_carlo(GameConfig::default(), AI, &bid, 2);
+        assert!((prob - mc_prob).abs() < 0.005, "{} vs {}", prob, mc_prob);
+        assert!(
+            (exact - mc_exact).abs() < 0.005,
+            "{} vs {}",
+            exact,
+            mc_exact
+        );
+
+        // 朴素二项分布约为 0.196，明显偏低
+        let binomial: f64 = (2..=5).map(|k| binomial_pmf(5, k, 1.0 / 6.0)).sum();
+        assert!(prob - binomial > 0.015);
+    }
+
+    #[test]
+    fn test_probability_matches_monte_carlo_multi_seat_wilds() {
+        let ai = AiEngine::new();
+        let config = GameConfig {
+            wild_ones: true,
+            players: vec![
+                PlayerConfig::human("You"),
+                PlayerConfig::ai("AI"),
+                PlayerConfig::ai("AI 2"),
+            ],
+            ..Default::default()
+        };
+        let observation = PlayerObservation::new(config.clone(), AI, vec![5, 5, 2, 3, 6]);
+        let bid = Bid::new(6, 5);
+
+        let prob = ai.belief(&observation).probability(&bid);
+        let exact = ai.belief(&observation).exact_probability(&bid);
+        let (mc_prob, mc_exact) = monte_carlo(config, AI, &bid, 4);
+        assert!((prob - mc_prob).abs() < 0.005, "{} vs {}", prob, mc_prob);
+        assert!(
+            (exact - mc_exact).abs() < 0.005,
+            "{} vs {}",
+            exact,
+            mc_exact
+        );
+    }
+
+    #[test]
+    fn test_bid_history_shifts_probability() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![5, 2, 3, 4, 6]);
+        let bid = Bid::new(4, 5);
+        let fresh = AiEngine::new().belief(&observation).probability(&bid);
+
+        observation
+            .bid_history
+            .push((HUMAN, Action::Bid(Bid::new(3, 5))));
+        observation
+            .bid_history
+            .push((AI, Action::Bid(Bid::new(3, 6))));
+        observation
+            .bid_history
+            .push((HUMAN, Action::Bid(Bid::new(4, 5))));
+        let trusting = AiEngine {
+            truthfulness: Some(0.9),
+            ..AiEngine::new()
+        };
+        let sceptical = AiEngine {
+            truthfulness: Some(0.0),
+            ..AiEngine::new()
+        };
+        let trusting = trusting.belief(&observation).probability(&bid);
+        let sceptical = sceptical.belief(&observation).probability(&bid);
+
+        assert!(trusting > fresh + 0.05);
+        assert!((sceptical - fresh).abs() < 1e-9);
+    }
+
+    /// 不同种子下 `difficulty` 的 AI 对同一局面的全部决策
+    fn decisions(difficulty: Difficulty, observation: &PlayerObservation) -> Vec<Action> {
+        (0..200)
+            .map(|seed| {
+                AiEngine::for_difficulty(difficulty)
+                    .with_seed(seed)
+                    .decide(observation)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_easy_never_calls_spot_on() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![3, 3, 3, 5, 6]);
+        observation.seats[HUMAN].dice_count = 1;
+        observation.current_bid = Some(Bid::new(3, 3));
+
+        assert!(!decisions(Difficulty::Easy, &observation).contains(&Action::SpotOn));
+    }
+
+    #[test]
+    fn test_easy_makes_mistakes_normal_does_not() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![5, 5, 5, 5, 2]);
+        observation.current_bid = Some(Bid::new(2, 5));
+
+        // 叫数必然成立，开就是失误
+        assert!(decisions(Difficulty::Easy, &observation).contains(&Action::Challenge));
+        assert!(!decisions(Difficulty::Normal, &observation).contains(&Action::Challenge));
+    }
+
+    #[test]
+    fn test_expert_bluffs_sometimes() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![2, 2, 3, 3, 4]);
+        observation.current_bid = Some(Bid::new(1, 2));
+
+        let holds = |action: &Action| match action {
+            Action::Bid(bid) => observation.my_count(bid.face, bid.pure) > 0,
+            _ => true,
+        };
+        assert!(decisions(Difficulty::Normal, &observation)
+            .iter()
+            .all(holds));
+        assert!(!decisions(Difficulty::Expert, &observation)
+            .iter()
+            .all(holds));
+    }
+
+    #[test]
+    fn test_bluff_sizes_are_mixed() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![2, 2, 3, 3, 4]);
+        observation.current_bid = Some(Bid::new(1, 2));
+
+        let bids: Vec<Bid> = (0..200)
+            .filter_map(|seed| {
+                match AiEngine::new()
+                    .with_bluff_rate(1.0)
+                    .with_seed(seed)
+                    .decide(&observation)
+                {
+                    Action::Bid(bid) => Some(bid),
+                    _ => None,
+                }
+            })
+            .collect();
+        assert_eq!(bids.len(), 200);
+        assert!(bids
+            .iter()
+            .all(|bid| observation.my_count(bid.face, bid.pure) == 0));
+        // 混合策略：不会每次都叫同一个诈叫
+        assert!(bids.iter().any(|bid| *bid != bids[0]));
+
+        // 诈叫倾向为 0 时即使是 Expert 也从不诈叫
+        for seed in 0..50 {
+            let action = AiEngine::for_difficulty(Difficulty::Expert)
+                .with_bluff_rate(0.0)
+                .with_seed(seed)
+                .decide(&observation);
+            if let Action::Bid(bid) = action {
+                assert!(observation.my_count(bid.face, bid.pure) > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hint_breaks_down_the_odds() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), HUMAN, vec![3, 3, 3, 5, 6]);
+        let mut advisor = AiEngine::for_difficulty(Difficulty::Expert).with_bluff_rate(0.0);
+
+        let opening = advisor.hint(&observation);
+        assert_eq!(opening.bid_probability, None);
+        assert!(matches!(opening.recommended, Action::Bid(_)));
+        // 没有叫数时每个点数的最小叫数是 1 个，手中有的点数必然成立
+        assert_eq!(opening.raises.len(), HINT_RAISES);
+        assert!(opening.raises.iter().all(|r| r.probability == 1.0));
+
+        observation.bid_history = vec![(AI, Action::Bid(Bid::new(9, 2)))];
+        observation.current_bid = Some(Bid::new(9, 2));
+        let hint = advisor.hint(&observation);
+        assert!(hint.bid_probability.unwrap() < 0.01);
+        assert!(hint.exact_probability.unwrap() <= hint.bid_probability.unwrap());
+        assert!(hint
+            .raises
+            .windows(2)
+            .all(|w| w[0].probability >= w[1].probability));
+        assert!(hint
+            .raises
+            .iter()
+            .all(|r| Bid::new(9, 2).is_valid_raise(&r.bid)));
+        assert_eq!(hint.recommended, Action::Challenge);
+    }
+
+    #[test]
+    fn test_reckless_jumps_higher_than_cautious() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![2, 2, 3, 3, 4]);
+        observation.current_bid = Some(Bid::new(1, 2));
+
+        let mean_count = |personality: Personality| {
+            let counts: Vec<u32> = (0..100)
+                .filter_map(|seed| {
+                    match AiEngine::new()
+                        .with_personality(personality)
+                        .with_seed(seed)
+                        .decide(&observation)
+                    {
+                        Action::Bid(bid) => Some(bid.count),
+                        _ => None,
+                    }
+                })
+                .collect();
+            counts.iter().sum::<u32>() as f64 / counts.len() as f64
+        };
+        assert!(mean_count(Personality::Reckless) > mean_count(Personality::Cautious) + 0.3);
+
+        // 稳健性格从不诈叫
+        for seed in 0..50 {
+            let action = AiEngine::for_difficulty(Difficulty::Expert)
+                .with_personality(Personality::Cautious)
+                .with_seed(seed)
+                .decide(&observation);
+            if let Action::Bid(bid) = action {
+                assert!(observation.my_count(bid.face, bid.pure) > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_big_bluffs_into_a_quick_challenger() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![2, 2, 3, 3, 4]);
+        observation.current_bid = Some(Bid::new(1, 2));
+        // 下家通常在叫数比期望还少半个时就开
+        observation.profiles[HUMAN] = Some(PlayerProfile {
+            challenges: 4,
+            challenge_margin_sum: -2.0,
+            ..Default::default()
+        });
+
+        let bluffs: Vec<Bid> = (0..100)
+            .filter_map(|seed| {
+                match AiEngine::new()
+                    .with_bluff_rate(1.0)
+                    .with_seed(seed)
+                    .decide(&observation)
+                {
+                    Action::Bid(bid) if observation.my_count(bid.face, bid.pure) == 0 => Some(bid),
+                    _ => None,
+                }
+            })
+            .collect();
+        assert!(!bluffs.is_empty());
+        // 10 颗骰子中期望 10/6 个，只有叫 1 个的诈叫不超过其门槛
+        assert!(bluffs.iter().all(|bid| bid.count == 1));
+    }
+
+    #[test]
+    fn test_easy_ignores_bid_history() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![5, 2, 3, 4, 6]);
+        let bid = Bid::new(4, 5);
+        let easy = AiEngine::for_difficulty(Difficulty::Easy);
+        let fresh = easy.belief(&observation).probability(&bid);
+
+        observation
+            .bid_history
+            .push((HUMAN, Action::Bid(Bid::new(3, 5))));
+        assert!((easy.belief(&observation).probability(&bid) - fresh).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_decisions_ignore_opponent_dice() {
+        let mut dice = SeededDice::new(5);
+        let mut state = GameState::with_dice(GameConfig::default(), &mut dice);
+        state.current_bid = Some(Bid::new(3, 4));
+        state.bid_history.push((HUMAN, Action::Bid(Bid::new(3, 4))));
+        let mut other = state.clone();
+        other.seats[HUMAN].dice = vec![4, 4, 4, 4, 4];
+
+        for difficulty in [Difficulty::Easy, Difficulty::Expert] {
+            assert_eq!(
+                decisions(difficulty, &state.observation_for(AI)),
+                decisions(difficulty, &other.observation_for(AI))
+            );
+        }
+    }
 }
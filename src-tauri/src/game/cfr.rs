@@ -0,0 +1,654 @@
+use crate::game::ai::{bid_modes, AiEngine};
+use crate::game::inference::HandPosterior;
+use crate::game::strategy::Strategy;
+use crate::game::types::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// CFR 求解支持的每人最多骰子数：3 颗骰子的信息集已达数百万，训练耗时与策略表体积都不实用
+pub const MAX_CFR_DICE: u32 = 2;
+/// 信息集记住的最近叫数个数；更早的叫数被抽象掉以控制规模
+const RECALL: usize = 3;
+/// 写入策略表时省略概率低于此值的行动
+const MIN_ACTION_PROBABILITY: f64 = 0.005;
+
+/// 离线 CFR 训练得到的 1v1 策略表。
+///
+/// 按双方骰子数分为若干子博弈，每个子博弈记录各信息集（自己的手牌与最近几个叫数）
+/// 上的平均策略。信息集不记住完整叫数历史，是为控制规模做的抽象
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StrategyTable {
+    pub dice_per_player: u32,
+    pub die_sides: u32,
+    pub wild_ones: bool,
+    pub reroll_straights: bool,
+    /// 每个子博弈的训练迭代次数
+    pub iterations: u32,
+    subgames: Vec<Subgame>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Subgame {
+    /// 行动方的骰子数
+    my_dice: u32,
+    /// 对手的骰子数
+    their_dice: u32,
+    strategies: HashMap<String, Vec<(Action, f64)>>,
+}
+
+impl StrategyTable {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let text = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    /// 该观察下的混合策略；对局不在表的覆盖范围内时返回 `None`：
+    /// 规则不同、不是两人对局、骰子过多或独骰局
+    pub fn lookup(&self, observation: &PlayerObservation) -> Option<&[(Action, f64)]> {
+        let config = &observation.config;
+        if config.die_sides != self.die_sides
+            || config.wild_ones != self.wild_ones
+            || config.reroll_straights != self.reroll_straights
+            || config.dice_per_player != self.dice_per_player
+            || observation.palifico.is_some()
+        {
+            return None;
+        }
+        let mut opponents = observation.opponents();
+        let opponent = opponents.next()?;
+        if opponents.next().is_some() {
+            return None;
+        }
+
+        let my_dice = observation.dice.len() as u32;
+        let their_dice = observation.seats[opponent].dice_count;
+        let subgame = self
+            .subgames
+            .iter()
+            .find(|s| s.my_dice == my_dice && s.their_dice == their_dice)?;
+        let bids: Vec<Bid> = observation
+            .bid_history
+            .iter()
+            .filter_map(|(_, action)| match action {
+                Action::Bid(bid) => Some(bid.clone()),
+                _ => None,
+            })
+            .collect();
+        let recent = &bids[bids.len().saturating_sub(RECALL)..];
+        let key = info_set_key(&observation.dice, recent);
+        subgame.strategies.get(&key).map(Vec::as_slice)
+    }
+}
+
+/// 为 `config` 的规则（含顺子重掷）训练策略表，双方骰子数各取 1..=dice_per_player。
+///
+/// 采用机会抽样的定策略迭代 CFR：每轮抽一组手牌，按叫数历史的拓扑序前向累计到达概率，
+/// 再反向计算收益并更新遗憾值。收益按结算计：开赢 +1 输 −1，定中 +SPOT_ON_STAKE
+pub fn train(config: &GameConfig, iterations: u32, seed: u64) -> Result<StrategyTable, String> {
+    if !(1..=MAX_CFR_DICE).contains(&config.dice_per_player) {
+        return Err(format!(
+            "CFR training supports 1 to {} dice per player",
+            MAX_CFR_DICE
+        ));
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut nodes: HashMap<(u32, u32), HashMap<String, RegretNode>> = HashMap::new();
+
+    for first in 1..=config.dice_per_player {
+        for second in first..=config.dice_per_player {
+            let mut trainer = Trainer::new(config, [first, second])?;
+            for iteration in 0..iterations {
+                trainer.iterate(&mut rng, (iteration % 2) as usize);
+            }
+            let dice = trainer.dice;
+            for (player, table) in trainer.into_info_sets().into_iter().enumerate() {
+                let counts = (dice[player], dice[1 - player]);
+                let merged = nodes.entry(counts).or_default();
+                for (key, node) in table {
+                    let entry = merged
+                        .entry(key)
+                        .or_insert_with(|| RegretNode::new(node.actions.clone()));
+                    entry.merge(node);
+                }
+            }
+        }
+    }
+
+    let mut subgames: Vec<Subgame> = nodes
+        .into_iter()
+        .map(|((my_dice, their_dice), table)| Subgame {
+            my_dice,
+            their_dice,
+            strategies: table
+                .into_iter()
+                .map(|(key, node)| (key, node.average_strategy()))
+                .collect(),
+        })
+        .collect();
+    subgames.sort_by_key(|s| (s.my_dice, s.their_dice));
+
+    Ok(StrategyTable {
+        dice_per_player: config.dice_per_player,
+        die_sides: config.die_sides,
+        wild_ones: config.wild_ones,
+        reroll_straights: config.reroll_straights,
+        iterations,
+        subgames,
+    })
+}
+
+/// 按策略表出手的 AI；表未覆盖的局面交给启发式 AI。
+/// 不对应任何难度，只作离线训练后对打用的基准对手
+pub struct CfrAi {
+    table: StrategyTable,
+    fallback: AiEngine,
+    rng: StdRng,
+}
+
+impl CfrAi {
+    pub fn new(table: StrategyTable) -> Self {
+        CfrAi {
+            table,
+            fallback: AiEngine::for_difficulty(Difficulty::Expert),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// 从训练器写出的文件加载
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        StrategyTable::load(path).map(Self::new)
+    }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        CfrAi {
+            fallback: self.fallback.with_seed(seed),
+            rng: StdRng::seed_from_u64(seed),
+            ..self
+        }
+    }
+}
+
+impl Strategy for CfrAi {
+    fn decide(&mut self, observation: &PlayerObservation) -> Action {
+        let Some(strategy) = self.table.lookup(observation) else {
+            return self.fallback.decide(observation);
+        };
+        let mut target = self.rng.gen::<f64>();
+        for (action, probability) in strategy {
+            target -= probability;
+            if target < 0.0 {
+                return action.clone();
+            }
+        }
+        strategy
+            .last()
+            .map(|(action, _)| action.clone())
+            .unwrap_or_else(|| self.fallback.decide(observation))
+    }
+}
+
+/// 信息集的键：排好序的手牌与最近的叫数，如 `2,5|2x3,3x4`（斋叫加 `*`，无叫数为 `-`）
+fn info_set_key(dice: &[u32], recent: &[Bid]) -> String {
+    let mut hand = dice.to_vec();
+    hand.sort_unstable();
+    let hand: Vec<String> = hand.iter().map(u32::to_string).collect();
+    let bids: Vec<String> = recent
+        .iter()
+        .map(|bid| {
+            format!(
+                "{}x{}{}",
+                bid.count,
+                bid.face,
+                if bid.pure { "*" } else { "" }
+            )
+        })
+        .collect();
+    let bids = if bids.is_empty() {
+        "-".to_string()
+    } else {
+        bids.join(",")
+    };
+    format!("{}|{}", hand.join(","), bids)
+}
+
+/// 场上共 `total` 颗骰子时全部合法叫数，从小到大排列
+fn bid_ladder(config: &GameConfig, total: u32) -> Vec<Bid> {
+    let mut bids: Vec<Bid> = (1..=total)
+        .flat_map(|count| {
+            (1..=config.die_sides).flat_map(move |face| {
+                bid_modes(config.wild_ones, face)
+                    .into_iter()
+                    .map(move |pure| Bid { count, face, pure })
+            })
+        })
+        .collect();
+    bids.sort_by(|a, b| {
+        if a.is_valid_raise(b) {
+            std::cmp::Ordering::Less
+        } else if b.is_valid_raise(a) {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    });
+    bids
+}
+
+/// 某信息集上的累计遗憾与累计策略
+struct RegretNode {
+    actions: Vec<Action>,
+    regret: Vec<f64>,
+    strategy_sum: Vec<f64>,
+}
+
+impl RegretNode {
+    fn new(actions: Vec<Action>) -> Self {
+        let n = actions.len();
+        RegretNode {
+            actions,
+            regret: vec![0.0; n],
+            strategy_sum: vec![0.0; n],
+        }
+    }
+
+    /// 遗憾匹配：按正遗憾成比例出手，全非正时均匀
+    fn strategy(&self) -> Vec<f64> {
+        let positive: Vec<f64> = self.regret.iter().map(|r| r.max(0.0)).collect();
+        let total: f64 = positive.iter().sum();
+        if total > 0.0 {
+            positive.iter().map(|r| r / total).collect()
+        } else {
+            vec![1.0 / self.actions.len() as f64; self.actions.len()]
+        }
+    }
+
+    fn merge(&mut self, other: RegretNode) {
+        for (sum, extra) in self.strategy_sum.iter_mut().zip(other.strategy_sum) {
+            *sum += extra;
+        }
+    }
+
+    /// 平均策略即近似均衡，省略极小概率的行动后重新归一
+    fn average_strategy(self) -> Vec<(Action, f64)> {
+        let total: f64 = self.strategy_sum.iter().sum();
+        let n = self.actions.len() as f64;
+        let average: Vec<f64> = self
+            .strategy_sum
+            .iter()
+            .map(|s| if total > 0.0 { s / total } else { 1.0 / n })
+            .collect();
+        let kept: f64 = average
+            .iter()
+            .filter(|&&p| p >= MIN_ACTION_PROBABILITY)
+            .sum();
+        self.actions
+            .into_iter()
+            .zip(average)
+            .filter(|(_, p)| *p >= MIN_ACTION_PROBABILITY)
+            .map(|(action, p)| (action, p / kept))
+            .collect()
+    }
+}
+
+/// 一对骰子数下的 CFR 训练。
+///
+/// 博弈节点为抽象后的叫数历史（最近 `RECALL` 个叫数）加行动方。叫数只增不减，
+/// 按末次叫数排序即为拓扑序，因此每轮只需一次前向、一次反向遍历
+struct Trainer<'a> {
+    config: &'a GameConfig,
+    dice: [u32; 2],
+    bids: Vec<Bid>,
+    histories: Vec<History>,
+    priors: [HandPosterior; 2],
+    /// 出现过的手牌，信息集以其下标标记
+    hands: Vec<Vec<u32>>,
+    hand_ids: HashMap<Vec<u32>, usize>,
+    /// 每名玩家的信息集，键为（手牌下标，历史下标）
+    nodes: [HashMap<(usize, usize), RegretNode>; 2],
+}
+
+/// 抽象后的叫数历史
+struct History {
+    /// 最近的叫数在 `bids` 中的下标，从早到晚
+    recent: Vec<usize>,
+    /// 每个可加注的叫数对应的后继历史
+    children: Vec<usize>,
+}
+
+impl History {
+    fn last(&self) -> Option<usize> {
+        self.recent.last().copied()
+    }
+}
+
+impl<'a> Trainer<'a> {
+    fn new(config: &'a GameConfig, dice: [u32; 2]) -> Result<Self, String> {
+        let prior = |n| {
+            HandPosterior::prior(n, config).ok_or_else(|| "Too many hands to solve".to_string())
+        };
+        let bids = bid_ladder(config, dice[0] + dice[1]);
+        Ok(Trainer {
+            config,
+            dice,
+            histories: histories(bids.len()),
+            bids,
+            priors: [prior(dice[0])?, prior(dice[1])?],
+            hands: Vec::new(),
+            hand_ids: HashMap::new(),
+            nodes: [HashMap::new(), HashMap::new()],
+        })
+    }
+
+    fn actions(&self, history: &History) -> Vec<Action> {
+        let mut actions = Vec::new();
+        if history.last().is_some() {
+            actions.push(Action::Challenge);
+            actions.push(Action::SpotOn);
+        }
+        let next = history.last().map_or(0, |i| i + 1);
+        actions.extend(self.bids[next..].iter().cloned().map(Action::Bid));
+        actions
+    }
+
+    fn hand_id(&mut self, hand: Vec<u32>) -> usize {
+        if let Some(&id) = self.hand_ids.get(&hand) {
+            return id;
+        }
+        self.hands.push(hand.clone());
+        self.hand_ids.insert(hand, self.hands.len() - 1);
+        self.hands.len() - 1
+    }
+
+    fn iterate(&mut self, rng: &mut StdRng, opener: usize) {
+        let hands = [self.priors[0].sample(rng), self.priors[1].sample(rng)];
+        let ids = [
+            self.hand_id(hands[0].clone()),
+            self.hand_id(hands[1].clone()),
+        ];
+        let count = self.histories.len();
+
+        // 前向：累计每个节点上双方的到达概率
+        let mut reach = vec![[[0.0f64; 2]; 2]; count];
+        let mut strategies: Vec<[Vec<f64>; 2]> = vec![[Vec::new(), Vec::new()]; count];
+        reach[0][opener] = [1.0, 1.0];
+        for h in 0..count {
+            for player in 0..2 {
+                if reach[h][player] == [0.0, 0.0] {
+                    continue;
+                }
+                let history = &self.histories[h];
+                let actions = self.actions(history);
+                let node = self.nodes[player]
+                    .entry((ids[player], h))
+                    .or_insert_with(|| RegretNode::new(actions));
+                let strategy = node.strategy();
+                let offset = strategy.len() - history.children.len();
+                for (&child, p) in history.children.iter().zip(&strategy[offset..]) {
+                    let mut r = reach[h][player];
+                    r[player] *= p;
+                    reach[child][1 - player][0] += r[0];
+                    reach[child][1 - player][1] += r[1];
+                }
+                strategies[h][player] = strategy;
+            }
+        }
+
+        // 反向：行动方视角的收益，更新遗憾与累计策略
+        let mut value = vec![[0.0f64; 2]; count];
+        for h in (0..count).rev() {
+            for player in 0..2 {
+                let strategy = &strategies[h][player];
+                if strategy.is_empty() {
+                    continue;
+                }
+                let history = &self.histories[h];
+                let mut utilities = Vec::with_capacity(strategy.len());
+                if let Some(last) = history.last() {
+                    utilities.push(self.settle(&hands, &self.bids[last], false));
+                    utilities.push(self.settle(&hands, &self.bids[last], true));
+                }
+                utilities.extend(history.children.iter().map(|&c| -value[c][1 - player]));
+                let node_value: f64 = strategy.iter().zip(&utilities).map(|(p, u)| p * u).sum();
+                value[h][player] = node_value;
+
+                let [own, other] = [reach[h][player][player], reach[h][player][1 - player]];
+                let node = self.nodes[player]
+                    .get_mut(&(ids[player], h))
+                    .expect("node visited in forward pass");
+                for i in 0..strategy.len() {
+                    node.regret[i] += other * (utilities[i] - node_value);
+                    node.strategy_sum[i] += own * strategy[i];
+                }
+            }
+        }
+    }
+
+    /// 对 `bid` 开（或定）的一方的收益
+    fn settle(&self, hands: &[Vec<u32>; 2], bid: &Bid, spot_on: bool) -> f64 {
+        let actual: u32 = hands
+            .iter()
+            .flatten()
+            .filter(|&&d| d == bid.face || (self.config.wild_ones && !bid.pure && d == WILD_FACE))
+            .count() as u32;
+        match (spot_on, actual == bid.count, actual < bid.count) {
+            (true, true, _) => SPOT_ON_STAKE as f64,
+            (true, false, _) => -1.0,
+            (false, _, true) => 1.0,
+            (false, _, false) => -1.0,
+        }
+    }
+
+    /// 训练完的信息集，键换成与手牌、叫数历史对应的字符串
+    fn into_info_sets(self) -> [HashMap<String, RegretNode>; 2] {
+        let Trainer {
+            bids,
+            histories,
+            hands,
+            nodes,
+            ..
+        } = self;
+        nodes.map(|table| {
+            table
+                .into_iter()
+                .map(|((hand, h), node)| {
+                    let recent: Vec<Bid> = histories[h]
+                        .recent
+                        .iter()
+                        .map(|&i| bids[i].clone())
+                        .collect();
+                    (info_set_key(&hands[hand], &recent), node)
+                })
+                .collect()
+        })
+    }
+}
+
+/// 枚举 `bid_count` 个叫数下全部抽象历史，按末次叫数排好拓扑序，首项为空历史
+fn histories(bid_count: usize) -> Vec<History> {
+    let mut recents = vec![Vec::new()];
+    let mut frontier = 0;
+    let mut seen: HashMap<Vec<usize>, usize> = HashMap::new();
+    seen.insert(Vec::new(), 0);
+    while frontier < recents.len() {
+        let recent: Vec<usize> = recents[frontier].clone();
+        let next = recent.last().map_or(0, |i| i + 1);
+        for bid in next..bid_count {
+            let child = extend(&recent, bid);
+            if !seen.contains_key(&child) {
+                seen.insert(child.clone(), recents.len());
+                recents.push(child);
+            }
+        }
+        frontier += 1;
+    }
+
+    recents.sort_by_key(|r| (r.last().map_or(0, |i| i + 1), r.len(), r.clone()));
+    let index: HashMap<&Vec<usize>, usize> =
+        recents.iter().enumerate().map(|(i, r)| (r, i)).collect();
+    recents
+        .iter()
+        .map(|recent| {
+            let next = recent.last().map_or(0, |i| i + 1);
+            History {
+                recent: recent.clone(),
+                children: (next..bid_count)
+                    .map(|bid| index[&extend(recent, bid)])
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// 历史追加一个叫数，只保留最近 `RECALL` 个
+fn extend(recent: &[usize], bid: usize) -> Vec<usize> {
+    let skip = (recent.len() + 1).saturating_sub(RECALL);
+    recent.iter().copied().chain(Some(bid)).skip(skip).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::dice::strategy_seed;
+    use crate::game::engine::GameEngine;
+
+    fn duel(dice_per_player: u32, wild_ones: bool) -> GameConfig {
+        GameConfig {
+            players: vec![PlayerConfig::ai("A"), PlayerConfig::ai("B")],
+            dice_per_player,
+            wild_ones,
+            reroll_straights: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bid_ladder_is_strictly_increasing() {
+        let ladder = bid_ladder(&duel(2, true), 4);
+        assert!(ladder.windows(2).all(|w| w[0].is_valid_raise(&w[1])));
+        // 4 个数量 × 5 个非 1 点 × 两种叫法 + 4 个 1 点斋叫
+        assert_eq!(ladder.len(), 44);
+    }
+
+    #[test]
+    fn test_train_rejects_large_games() {
+        assert!(train(&duel(MAX_CFR_DICE + 1, false), 10, 0).is_err());
+    }
+
+    #[test]
+    fn test_solved_ai_challenges_impossible_bid() {
+        let config = duel(1, false);
+        let table = train(&config, 5_000, 7).unwrap();
+        let mut ai = CfrAi::new(table).with_seed(1);
+        let mut observation = PlayerObservation::new(config, 1, vec![3]);
+        observation.current_bid = Some(Bid {
+            count: 2,
+            face: 6,
+            pure: false,
+        });
+        observation.bid_history = vec![(0, Action::Bid(observation.current_bid.clone().unwrap()))];
+        for _ in 0..20 {
+            assert_eq!(ai.decide(&observation), Action::Challenge);
+        }
+    }
+
+    /// 策略表对 `difficulty` 的启发式 AI 交替座位打 24 场，返回每局的平均收益。
+    /// 定中的赌注翻倍，因此按收益而非胜局数衡量
+    fn average_payoff(table: &StrategyTable, config: &GameConfig, difficulty: Difficulty) -> f64 {
+        let (mut payoff, mut played) = (0.0, 0);
+        for m in 0..24u64 {
+            let seat = (m % 2) as usize;
+            let mut engine = GameEngine::new();
+            engine
+                .start_game(GameConfig {
+                    seed: Some(m),
+                    ..config.clone()
+                })
+                .unwrap();
+            let cfr = CfrAi::new(table.clone()).with_seed(strategy_seed(m, seat));
+            let heuristic =
+                AiEngine::for_difficulty(difficulty).with_seed(strategy_seed(m, 1 - seat));
+            engine.set_strategy(seat, Box::new(cfr)).unwrap();
+            engine.set_strategy(1 - seat, Box::new(heuristic)).unwrap();
+            // 首局由默认 AI 开局，只统计换上策略后的各局
+            while engine.next_round(0).is_ok() {
+                if let GamePhase::RoundOver(result) = &engine.state.phase {
+                    let stake = result.stake as f64;
+                    payoff += if result.winner == seat { stake } else { -stake };
+                    played += 1;
+                }
+            }
+        }
+        payoff / played as f64
+    }
+
+    #[test]
+    fn test_solved_ai_beats_easy_and_matches_expert() {
+        let config = GameConfig {
+            max_rounds: 200,
+            opener_rule: OpenerRule::Alternate,
+            ..duel(1, false)
+        };
+        let table = train(&config, 20_000, 11).unwrap();
+        let untrained = train(&config, 1, 11).unwrap();
+
+        // 稳赢常犯错的 Easy；对 Expert 不吃亏（每局标准误约 0.02），而未训练的表明显吃亏
+        let easy = average_payoff(&table, &config, Difficulty::Easy);
+        assert!(easy > 0.1, "CFR averaged {:.3} against Easy", easy);
+        let expert = average_payoff(&table, &config, Difficulty::Expert);
+        assert!(expert > -0.05, "CFR averaged {:.3} against Expert", expert);
+        let baseline = average_payoff(&untrained, &config, Difficulty::Expert);
+        assert!(
+            expert > baseline + 0.05,
+            "trained {:.3}, untrained {:.3}",
+            expert,
+            baseline
+        );
+    }
+
+    #[test]
+    fn test_lookup_only_covers_trained_duels() {
+        let config = duel(1, false);
+        let table = train(&config, 100, 0).unwrap();
+        let observation = PlayerObservation::new(config.clone(), 0, vec![4]);
+        assert!(table.lookup(&observation).is_some());
+
+        let wilds = PlayerObservation::new(duel(1, true), 0, vec![4]);
+        assert!(table.lookup(&wilds).is_none());
+
+        let mut crowd = config;
+        crowd.players.push(PlayerConfig::ai("C"));
+        let crowded = PlayerObservation::new(crowd, 0, vec![4]);
+        assert!(table.lookup(&crowded).is_none());
+        // 表外局面交给启发式 AI，仍给出合法开局叫数
+        let mut ai = CfrAi::new(table).with_seed(0);
+        assert!(matches!(ai.decide(&crowded), Action::Bid(_)));
+    }
+
+    #[test]
+    fn test_table_round_trips_through_file() {
+        let table = train(&duel(1, true), 200, 3).unwrap();
+        assert_eq!(table.subgames.len(), 1);
+        let path = std::env::temp_dir().join(format!("cfr-table-{}.json", std::process::id()));
+        table.save(&path).unwrap();
+        let loaded = StrategyTable::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // JSON 不保证浮点数逐位还原，按容差比较
+        for (a, b) in loaded.subgames.iter().zip(&table.subgames) {
+            assert_eq!(a.strategies.len(), b.strategies.len());
+            for (key, strategy) in &b.strategies {
+                for ((x, p), (y, q)) in a.strategies[key].iter().zip(strategy) {
+                    assert_eq!(x, y);
+                    assert!((p - q).abs() < 1e-12);
+                }
+            }
+        }
+    }
+}
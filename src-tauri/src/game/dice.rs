@@ -0,0 +1,122 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+#[cfg(test)]
+use std::collections::VecDeque;
+
+/// 骰子来源：引擎的全部随机性（掷骰、随机先叫）都经由它产生
+pub trait DiceSource: Send {
+    /// 掷一颗 `sides` 面骰，返回 1..=sides
+    fn roll(&mut self, sides: u32) -> u32;
+
+    /// 可用于重放的种子；脚本等非随机来源为 `None`
+    fn seed(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// 由种子确定的伪随机骰子：同一种子、同样的操作序列会得到完全相同的对局
+pub struct SeededDice {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl SeededDice {
+    pub fn new(seed: u64) -> Self {
+        SeededDice {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// 随机生成种子。种子限制在 53 位以内，前端以 JS number 传回时不丢精度
+    pub fn from_entropy() -> Self {
+        Self::new(rand::random::<u64>() >> 11)
+    }
+}
+
+impl DiceSource for SeededDice {
+    fn roll(&mut self, sides: u32) -> u32 {
+        self.rng.gen_range(1..=sides)
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+}
+
+/// 由本场种子为某个座位的 AI 派生随机种子。
+/// 不能直接用骰子种子：AI 抽样手牌与掷骰用的是同一种生成器，会重放出发到手中的骰子。
+/// 这里加盐后经 SplitMix64 混合，各座位、各场的种子彼此无关
+pub fn strategy_seed(seed: u64, seat: usize) -> u64 {
+    const SALT: u64 = 0x5EED_A15E_ED5E_A7ED;
+    let mut z = (seed ^ SALT).wrapping_add((seat as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// 按脚本依次给出点数，用完后从头循环；用于在测试中脚本化整局
+#[cfg(test)]
+pub struct ScriptedDice {
+    faces: VecDeque<u32>,
+}
+
+#[cfg(test)]
+impl ScriptedDice {
+    pub fn new(faces: impl IntoIterator<Item = u32>) -> Self {
+        let faces: VecDeque<u32> = faces.into_iter().collect();
+        assert!(!faces.is_empty(), "ScriptedDice needs at least one face");
+        ScriptedDice { faces }
+    }
+}
+
+#[cfg(test)]
+impl DiceSource for ScriptedDice {
+    fn roll(&mut self, _sides: u32) -> u32 {
+        let face = self.faces.pop_front().unwrap_or(1);
+        self.faces.push_back(face);
+        face
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_dice_is_reproducible() {
+        let mut a = SeededDice::new(42);
+        let mut b = SeededDice::new(42);
+        let first: Vec<u32> = (0..20).map(|_| a.roll(6)).collect();
+        let second: Vec<u32> = (0..20).map(|_| b.roll(6)).collect();
+        assert_eq!(first, second);
+        assert!(first.iter().all(|&d| (1..=6).contains(&d)));
+        assert_eq!(a.seed(), Some(42));
+    }
+
+    #[test]
+    fn test_entropy_seed_fits_in_js_number() {
+        for _ in 0..100 {
+            let seed = SeededDice::from_entropy().seed().unwrap();
+            assert!(seed < 1 << 53);
+        }
+    }
+
+    #[test]
+    fn test_strategy_seeds_differ_from_dice_seed() {
+        let seeds: Vec<u64> = (0..4).map(|seat| strategy_seed(42, seat)).collect();
+        assert!(seeds.iter().all(|&s| s != 42));
+        assert!(seeds
+            .windows(2)
+            .all(|w| w[0] != w[1] && w[0].abs_diff(w[1]) > 1));
+        assert_ne!(strategy_seed(43, 0), seeds[0]);
+    }
+
+    #[test]
+    fn test_scripted_dice_cycles() {
+        let mut dice = ScriptedDice::new([3, 5]);
+        let rolled: Vec<u32> = (0..5).map(|_| dice.roll(6)).collect();
+        assert_eq!(rolled, vec![3, 5, 3, 5, 3]);
+        assert_eq!(dice.seed(), None);
+    }
+}
@@ -1,151 +1,548 @@
 use crate::game::ai::AiEngine;
+use crate::game::dice::{strategy_seed, DiceSource, SeededDice};
+use crate::game::mcts::{MonteCarloAi, SearchBudget, DEFAULT_ITERATIONS};
+use crate::game::profile::{PlayerProfile, ProfileBook};
+use crate::game::report::MatchReport;
+use crate::game::strategy::Strategy;
 use crate::game::types::*;
+use std::path::PathBuf;
 
 pub struct GameEngine {
     pub state: GameState,
-    ai: AiEngine,
+    /// 按座位排列的 AI 策略，人类座位为 `None`
+    strategies: Vec<Option<Box<dyn Strategy>>>,
+    /// 本场的骰子来源，掷骰与随机先叫都从这里取
+    dice: Box<dyn DiceSource>,
+    /// 人类玩家的历史档案，跨场保留，每局结束时更新
+    profiles: ProfileBook,
+    /// 档案文件；设置后每局结束时写回
+    profile_path: Option<PathBuf>,
+    /// 档案读写最近出现的问题，随视图报告给前端；写档成功后清除
+    profile_warning: Option<String>,
+}
+
+impl Default for GameEngine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GameEngine {
     pub fn new() -> Self {
+        let state = GameState::new();
         GameEngine {
-            state: GameState::new(),
-            ai: AiEngine::new(),
+            strategies: Self::default_strategies(&state.config, rand::random()),
+            state,
+            dice: Box::new(SeededDice::from_entropy()),
+            profiles: ProfileBook::default(),
+            profile_path: None,
+            profile_warning: None,
+        }
+    }
+
+    /// 从文件加载人类玩家档案，此后每局结束时写回该文件。
+    /// 读取失败时以空档案开始：原文件先改名为 `.bak` 保留，改名也失败则本次不写回，
+    /// 以免覆盖其中的档案。问题记入 `profile_warning`
+    pub fn load_profiles(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        match ProfileBook::load(&path) {
+            Ok(book) => {
+                self.profiles = book;
+                self.profile_path = Some(path);
+                self.profile_warning = None;
+            }
+            Err(e) => {
+                self.profiles = ProfileBook::default();
+                let backup = path.with_extension("json.bak");
+                match std::fs::rename(&path, &backup) {
+                    Ok(()) => {
+                        self.profile_warning = Some(format!(
+                            "Could not load profiles ({}); moved the file to {}",
+                            e,
+                            backup.display()
+                        ));
+                        self.profile_path = Some(path);
+                    }
+                    Err(backup_error) => {
+                        self.profile_warning = Some(format!(
+                            "Could not load profiles ({}) or back them up ({}); profiles will not be saved",
+                            e, backup_error
+                        ));
+                        self.profile_path = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// 档案读写的问题，例如损坏的档案已备份、写档失败
+    pub fn profile_warning(&self) -> Option<&str> {
+        self.profile_warning.as_deref()
+    }
+
+    /// `seat` 的视角，附上档案读写的问题
+    pub fn view(&self, seat: usize) -> GameView {
+        GameView {
+            profile_warning: self.profile_warning.clone(),
+            ..self.state.view_for(seat)
+        }
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&PlayerProfile> {
+        self.profiles.get(name)
+    }
+
+    /// `seat` 的观察，附上桌上人类玩家的档案
+    fn observation_for(&self, seat: usize) -> PlayerObservation {
+        let mut observation = self.state.observation_for(seat);
+        observation.profiles = self
+            .state
+            .seats
+            .iter()
+            .map(|s| match s.kind {
+                PlayerKind::Human => self.profiles.get(&s.name).cloned(),
+                PlayerKind::AI => None,
+            })
+            .collect();
+        observation
+    }
+
+    /// 开始新的一场，返回第一个人类座位的视角。
+    /// 骰子由 `config.seed` 决定，未指定时随机生成种子
+    pub fn start_game(&mut self, config: GameConfig) -> Result<GameView, String> {
+        let dice = match config.seed {
+            Some(seed) => SeededDice::new(seed),
+            None => SeededDice::from_entropy(),
+        };
+        self.start_game_with_dice(config, Box::new(dice))
+    }
+
+    /// 用指定的骰子来源开始新的一场，可用于脚本化整局或重放
+    pub fn start_game_with_dice(
+        &mut self,
+        config: GameConfig,
+        mut dice: Box<dyn DiceSource>,
+    ) -> Result<GameView, String> {
+        config.validate()?;
+        // AI 的失误与诈叫同样随种子确定，便于重放
+        let ai_seed = dice.seed().unwrap_or_else(rand::random);
+        self.strategies = Self::default_strategies(&config, ai_seed);
+        self.state = GameState::with_dice(config, dice.as_mut());
+        self.dice = dice;
+        let opener = self.first_round_opener();
+        self.begin_turns(opener);
+        Ok(self.view(self.default_seat()))
+    }
+
+    /// 每个 AI 座位一个按难度（及座位的性格、诈叫倾向）配置的策略：
+    /// Master 为蒙特卡洛 AI，不受性格影响，其余为 `AiEngine`。各座位的随机种子由 `strategy_seed` 派生
+    fn default_strategies(config: &GameConfig, seed: u64) -> Vec<Option<Box<dyn Strategy>>> {
+        config
+            .players
+            .iter()
+            .enumerate()
+            .map(|(i, player)| {
+                let seed = strategy_seed(seed, i);
+                let strategy: Box<dyn Strategy> = match (player.kind, config.difficulty) {
+                    (PlayerKind::Human, _) => return None,
+                    (PlayerKind::AI, Difficulty::Master) => {
+                        let budget = Self::search_budget(config);
+                        Box::new(MonteCarloAi::new(budget).with_seed(seed))
+                    }
+                    (PlayerKind::AI, difficulty) => {
+                        let mut ai = AiEngine::for_difficulty(difficulty).with_seed(seed);
+                        if let Some(personality) = player.personality {
+                            ai = ai.with_personality(personality);
+                        }
+                        if let Some(rate) = player.bluff_rate {
+                            ai = ai.with_bluff_rate(rate);
+                        }
+                        Box::new(ai)
+                    }
+                };
+                Some(strategy)
+            })
+            .collect()
+    }
+
+    /// Master 的搜索预算。指定种子的对局只按抽样组数停止，决策不随机器快慢变化，
+    /// 保证整场可重放；未指定种子的交互对局另加用时上限，避免界面久等
+    fn search_budget(config: &GameConfig) -> SearchBudget {
+        match config.seed {
+            Some(_) => SearchBudget::fixed(DEFAULT_ITERATIONS),
+            None => SearchBudget::default(),
         }
     }
 
-    pub fn start_game(&mut self) -> GameView {
-        self.state = GameState::new();
-        self.state.to_view()
+    /// 替换某个 AI 座位的策略，从该座位下一次行动起生效；新开一场时恢复默认 AI
+    pub fn set_strategy(&mut self, seat: usize, strategy: Box<dyn Strategy>) -> Result<(), String> {
+        let Some(player) = self.state.seats.get(seat) else {
+            return Err("No such seat".to_string());
+        };
+        if player.kind != PlayerKind::AI {
+            return Err("Seat is not controlled by the AI".to_string());
+        }
+        self.strategies[seat] = Some(strategy);
+        Ok(())
     }
 
     pub fn new_round(&mut self) {
-        self.state.roll_all_dice();
-        self.state.phase = GamePhase::PlayerTurn;
-        self.state.current_turn = Player::Human;
+        let opener = self.round_opener();
+        self.state.palifico = self.next_palifico();
+        self.state.roll_all_dice(self.dice.as_mut());
+
+        // 独骰局由只剩 1 颗骰子的玩家先叫
+        self.begin_turns(self.state.palifico.unwrap_or(opener));
+    }
+
+    /// 视图默认面向的座位：第一个人类座位，全 AI 桌则为 0 号座位
+    pub fn default_seat(&self) -> usize {
+        self.state
+            .seats
+            .iter()
+            .position(|s| s.kind == PlayerKind::Human)
+            .unwrap_or(0)
+    }
+
+    fn first_round_opener(&mut self) -> usize {
+        match self.state.config.opener_rule {
+            OpenerRule::Random => self.random_active_seat(),
+            _ => self.state.config.first_opener,
+        }
+    }
+
+    /// 按 `OpenerRule` 决定下一局的先叫座位，须在重掷前（上局结果仍在时）调用。
+    /// 选中的座位已出局时由其下家先叫
+    fn round_opener(&mut self) -> usize {
+        let last = self.state.last_round_result.as_ref();
+        let seat = match self.state.config.opener_rule {
+            OpenerRule::Fixed => self.state.config.first_opener,
+            OpenerRule::LoserOpens => last.map_or(self.state.opener, |r| r.loser),
+            OpenerRule::WinnerOpens => last.map_or(self.state.opener, |r| r.winner),
+            OpenerRule::Alternate => return self.state.next_seat(self.state.opener),
+            OpenerRule::Random => return self.random_active_seat(),
+        };
+        if self.state.seats[seat].is_active() {
+            seat
+        } else {
+            self.state.next_seat(seat)
+        }
     }
 
-    pub fn player_bid(&mut self, count: u32, face: u32) -> Result<GameView, String> {
-        if self.state.phase != GamePhase::PlayerTurn {
+    fn random_active_seat(&mut self) -> usize {
+        let active: Vec<usize> = self.state.active_seats().collect();
+        let pick = self.dice.roll(active.len() as u32) as usize;
+        active[pick - 1]
+    }
+
+    /// 由 `opener` 开始本局；轮到 AI 时立即代为行动，直到轮到人类
+    fn begin_turns(&mut self, opener: usize) {
+        self.state.opener = opener;
+        self.state.current_turn = opener;
+        self.state.phase = GamePhase::Turn(opener);
+        self.run_ai_turns();
+    }
+
+    /// 上一局输家刚好降到 1 颗骰子时，下一局为其独骰局。
+    /// 骰子只减不增，因此每名玩家至多触发一次
+    fn next_palifico(&self) -> Option<usize> {
+        if !self.state.config.palifico || self.state.config.format != MatchFormat::DiceLoss {
+            return None;
+        }
+        let loser = self.state.last_round_result.as_ref()?.loser;
+        (self.state.seats[loser].dice_count == 1).then_some(loser)
+    }
+
+    fn check_human_turn(&self, seat: usize) -> Result<(), String> {
+        let Some(player) = self.state.seats.get(seat) else {
+            return Err("No such seat".to_string());
+        };
+        if player.kind != PlayerKind::Human {
+            return Err("Seat is not controlled by a human".to_string());
+        }
+        if self.state.phase != GamePhase::Turn(seat) {
             return Err("Not player's turn".to_string());
         }
+        Ok(())
+    }
+
+    /// 轮到人类座位时的提示，用不诈叫的 Expert AI 从该座位视角计算
+    pub fn hint(&self, seat: usize) -> Result<Hint, String> {
+        self.check_human_turn(seat)?;
+        let mut advisor = AiEngine::for_difficulty(Difficulty::Expert)
+            .with_bluff_rate(0.0)
+            .with_seed(0);
+        Ok(advisor.hint(&self.observation_for(seat)))
+    }
 
-        if !(1..=6).contains(&face) {
-            return Err("Face must be between 1 and 6".to_string());
+    /// 整场结束后的复盘
+    pub fn match_report(&self) -> Result<MatchReport, String> {
+        match self.state.phase {
+            GamePhase::GameOver { .. } => Ok(MatchReport::new(&self.state)),
+            _ => Err("Match is not over yet".to_string()),
         }
+    }
+
+    pub fn player_bid(
+        &mut self,
+        seat: usize,
+        count: u32,
+        face: u32,
+        pure: bool,
+    ) -> Result<GameView, String> {
+        self.check_human_turn(seat)?;
+
         if count < 1 {
             return Err("Count must be at least 1".to_string());
         }
 
-        let new_bid = Bid { count, face };
-        if let Some(ref current) = self.state.current_bid {
-            if !current.is_valid_raise(&new_bid) {
-                return Err("Bid must raise the current bid".to_string());
-            }
+        let new_bid = self.state.validate_bid(Bid { count, face, pure })?;
+
+        self.apply_action(seat, Action::Bid(new_bid));
+        self.run_ai_turns();
+        Ok(self.view(seat))
+    }
+
+    pub fn player_challenge(&mut self, seat: usize) -> Result<GameView, String> {
+        self.check_human_turn(seat)?;
+        if self.state.current_bid.is_none() {
+            return Err("No bid to challenge".to_string());
         }
 
-        self.state.current_bid = Some(new_bid.clone());
-        self.state
-            .bid_history
-            .push((Player::Human, Action::Bid(new_bid)));
-        self.state.current_turn = Player::AI;
-        self.state.phase = GamePhase::AITurn;
+        self.apply_action(seat, Action::Challenge);
+        Ok(self.view(seat))
+    }
+
+    pub fn player_spot_on(&mut self, seat: usize) -> Result<GameView, String> {
+        self.check_human_turn(seat)?;
+        if self.state.current_bid.is_none() {
+            return Err("No bid to call spot on".to_string());
+        }
 
-        let ai_action = self.ai.decide(&self.state);
-        self.execute_ai_action(ai_action)
+        self.apply_action(seat, Action::SpotOn);
+        Ok(self.view(seat))
     }
 
-    pub fn player_challenge(&mut self) -> Result<GameView, String> {
-        if self.state.phase != GamePhase::PlayerTurn {
-            return Err("Not player's turn".to_string());
+    /// 依次执行 AI 座位的行动，直到轮到人类座位或本局结束
+    fn run_ai_turns(&mut self) {
+        while let GamePhase::Turn(seat) = self.state.phase {
+            if self.state.seats[seat].kind != PlayerKind::AI {
+                break;
+            }
+            let observation = self.observation_for(seat);
+            let Some(strategy) = self.strategies[seat].as_mut() else {
+                break;
+            };
+            let ai_action = strategy.decide(&observation);
+            let ai_action = self.legal_action(ai_action);
+            if let Some(personality) = self.state.seats[seat].personality {
+                let turn = self.state.bid_history.len();
+                self.state.taunts.push(Taunt {
+                    seat,
+                    action: turn,
+                    line: personality.line(&ai_action, turn).to_string(),
+                });
+            }
+            self.apply_action(seat, ai_action);
         }
-        if self.state.current_bid.is_none() {
-            return Err("No bid to challenge".to_string());
+    }
+
+    /// 策略给出非法行动时的兜底：叫数按规则规范，
+    /// 不合法的行动在有叫数时改为开，否则改为最小叫数
+    fn legal_action(&self, action: Action) -> Action {
+        match (action, &self.state.current_bid) {
+            (Action::Bid(bid), _) => match self.state.validate_bid(bid) {
+                Ok(bid) => Action::Bid(bid),
+                Err(_) if self.state.current_bid.is_some() => Action::Challenge,
+                Err(_) => self.opening_fallback(),
+            },
+            (call, Some(_)) => call,
+            (_, None) => self.opening_fallback(),
         }
+    }
 
-        self.state
-            .bid_history
-            .push((Player::Human, Action::Challenge));
-        let result = self.resolve_challenge(Player::Human);
-        self.apply_round_result(&result);
-        Ok(self.state.to_view())
+    fn opening_fallback(&self) -> Action {
+        let bid = Bid::new(1, self.state.locked_face().unwrap_or(WILD_FACE));
+        Action::Bid(
+            self.state
+                .normalize_bid(bid)
+                .unwrap_or(Bid::new(1, WILD_FACE)),
+        )
     }
 
-    fn execute_ai_action(&mut self, action: Action) -> Result<GameView, String> {
+    fn apply_action(&mut self, seat: usize, action: Action) {
+        self.state.bid_history.push((seat, action.clone()));
         match action {
             Action::Bid(bid) => {
-                self.state.current_bid = Some(bid.clone());
-                self.state.bid_history.push((Player::AI, Action::Bid(bid)));
-                self.state.current_turn = Player::Human;
-                self.state.phase = GamePhase::PlayerTurn;
-                Ok(self.state.to_view())
+                self.state.current_bid = Some(bid);
+                let next = self.state.next_seat(seat);
+                self.state.current_turn = next;
+                self.state.phase = GamePhase::Turn(next);
             }
             Action::Challenge => {
-                self.state.bid_history.push((Player::AI, Action::Challenge));
-                let result = self.resolve_challenge(Player::AI);
+                let result = self.resolve_challenge(seat);
+                self.apply_round_result(&result);
+            }
+            Action::SpotOn => {
+                let result = self.resolve_spot_on(seat);
                 self.apply_round_result(&result);
-                Ok(self.state.to_view())
             }
         }
     }
 
-    fn resolve_challenge(&self, challenger: Player) -> RoundResult {
+    fn resolve_challenge(&self, challenger: usize) -> RoundResult {
+        self.resolve_call(challenger, false)
+    }
+
+    fn resolve_spot_on(&self, caller: usize) -> RoundResult {
+        self.resolve_call(caller, true)
+    }
+
+    /// 结算「开」或「定」：开时实际数量不足叫数则开的人赢；
+    /// 定时实际数量恰好等于叫数则定的人赢双倍，否则叫的人赢
+    fn resolve_call(&self, caller: usize, spot_on: bool) -> RoundResult {
         let bid = self
             .state
             .current_bid
             .as_ref()
             .expect("challenge requires a bid");
-        let actual_count = self.state.count_face(bid.face);
+        let bidder = self
+            .state
+            .last_bidder()
+            .expect("challenge requires a bidder");
+        let actual_count = self.state.count_face(bid.face, bid.pure);
+        let wild_count = self.state.count_wilds(bid.face, bid.pure);
 
-        let bidder = match challenger {
-            Player::Human => Player::AI,
-            Player::AI => Player::Human,
+        let caller_wins = if spot_on {
+            actual_count == bid.count
+        } else {
+            actual_count < bid.count
         };
-
-        let (winner, loser) = if actual_count >= bid.count {
-            (bidder, challenger)
+        let (winner, loser) = if caller_wins {
+            (caller, bidder)
         } else {
-            (challenger, bidder)
+            (bidder, caller)
+        };
+        let stake = if spot_on && caller_wins {
+            SPOT_ON_STAKE
+        } else {
+            1
         };
 
         RoundResult {
             round: self.state.current_round,
             winner,
             loser,
-            human_dice: self.state.human_dice.clone(),
-            ai_dice: self.state.ai_dice.clone(),
+            dice: self.state.seats.iter().map(|s| s.dice.clone()).collect(),
+            bids: self.state.review_bids(),
+            call: self.state.review_call(caller, spot_on),
             last_bid: bid.clone(),
             actual_count,
+            wild_count,
+            spot_on,
+            palifico: self.state.palifico,
+            stake,
+            seed: self.state.seed,
         }
     }
 
     fn apply_round_result(&mut self, result: &RoundResult) {
+        self.record_profiles();
         self.state.last_round_result = Some(result.clone());
+        self.state.rounds.push(CompletedRound {
+            opener: self.state.opener,
+            bid_history: self.state.bid_history.clone(),
+            taunts: self.state.taunts.clone(),
+            result: result.clone(),
+        });
+        self.state.seats[result.winner].wins += result.stake;
+
+        if self.state.config.format == MatchFormat::DiceLoss {
+            let loser = &mut self.state.seats[result.loser];
+            loser.dice_count = loser.dice_count.saturating_sub(result.stake);
 
-        match result.winner {
-            Player::Human => self.state.human_wins += 1,
-            Player::AI => self.state.ai_wins += 1,
+            // 只剩一个座位还有骰子时整场结束
+            let active: Vec<usize> = self.state.active_seats().collect();
+            self.state.phase = match active[..] {
+                [winner] => GamePhase::GameOver {
+                    winner: Some(winner),
+                },
+                _ => GamePhase::RoundOver(result.clone()),
+            };
+            return;
         }
 
         if self.state.current_round >= self.state.max_rounds {
-            let winner = if self.state.human_wins >= self.state.ai_wins {
-                Player::Human
-            } else {
-                Player::AI
+            let leaders = self.leaders();
+            self.state.phase = match (&leaders[..], self.state.config.tie_break) {
+                (&[winner], _) => GamePhase::GameOver {
+                    winner: Some(winner),
+                },
+                // 加赛：继续下一局，局末再判
+                (_, TieBreak::SuddenDeath) => GamePhase::RoundOver(result.clone()),
+                (tied, _) => GamePhase::GameOver {
+                    winner: self.break_tie(tied, result),
+                },
             };
-            self.state.phase = GamePhase::GameOver { winner };
             return;
         }
 
         self.state.phase = GamePhase::RoundOver(result.clone());
     }
 
-    pub fn next_round(&mut self) -> Result<GameView, String> {
+    /// 骰子公开后更新人类玩家的档案。写档失败不影响对局，记入 `profile_warning`，
+    /// 下一局结束时会再次写入
+    fn record_profiles(&mut self) {
+        self.profiles.record_round(&self.state);
+        if let Some(path) = &self.profile_path {
+            self.profile_warning = self
+                .profiles
+                .save(path)
+                .err()
+                .map(|e| format!("Could not save profiles: {}", e));
+        }
+    }
+
+    /// 胜场最多的座位
+    fn leaders(&self) -> Vec<usize> {
+        let best = self.state.seats.iter().map(|s| s.wins).max().unwrap_or(0);
+        (0..self.state.seats.len())
+            .filter(|&i| self.state.seats[i].wins == best)
+            .collect()
+    }
+
+    /// 按配置的 `TieBreak` 在胜场并列的座位中决出胜者，`None` 为平局
+    fn break_tie(&self, tied: &[usize], last: &RoundResult) -> Option<usize> {
+        match self.state.config.tie_break {
+            TieBreak::Draw | TieBreak::SuddenDeath => None,
+            TieBreak::MostDice => {
+                let seats = &self.state.seats;
+                let most = tied.iter().map(|&i| seats[i].dice_count).max()?;
+                let top: Vec<usize> = tied
+                    .iter()
+                    .copied()
+                    .filter(|&i| seats[i].dice_count == most)
+                    .collect();
+                match top[..] {
+                    [winner] => Some(winner),
+                    _ => None,
+                }
+            }
+            TieBreak::SeatOrder => tied.first().copied(),
+            TieBreak::LastRoundWinner if tied.contains(&last.winner) => Some(last.winner),
+            TieBreak::LastRoundWinner => tied.first().copied(),
+        }
+    }
+
+    pub fn next_round(&mut self, seat: usize) -> Result<GameView, String> {
+        if seat >= self.state.seats.len() {
+            return Err("No such seat".to_string());
+        }
         match &self.state.phase {
             GamePhase::RoundOver(_) => {
                 self.state.current_round += 1;
                 self.new_round();
-                Ok(self.state.to_view())
+                Ok(self.view(seat))
             }
             GamePhase::GameOver { .. } => Err("Game is over".to_string()),
             _ => Err("Can only move to next round after round over".to_string()),
@@ -156,105 +553,1069 @@ impl GameEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game::dice::ScriptedDice;
+
+    const HUMAN: usize = 0;
+    const AI: usize = 1;
 
-    fn make_result(round: u32, winner: Player, loser: Player) -> RoundResult {
+    fn make_result(round: u32, winner: usize, loser: usize) -> RoundResult {
         RoundResult {
             round,
             winner,
             loser,
-            human_dice: vec![1, 2, 3, 4, 5],
-            ai_dice: vec![1, 1, 3, 5, 6],
-            last_bid: Bid { count: 3, face: 1 },
+            dice: vec![vec![1, 2, 3, 4, 5], vec![1, 1, 3, 5, 6]],
+            bids: Vec::new(),
+            call: CallReview {
+                seat: loser,
+                probability: 0.5,
+                exact_probability: 0.25,
+                expected_value: 0.0,
+                alternative_value: -0.25,
+            },
+            last_bid: Bid::new(3, 1),
             actual_count: 3,
+            wild_count: 0,
+            spot_on: false,
+            palifico: None,
+            stake: 1,
+            seed: None,
+        }
+    }
+
+    /// 直接摆上一个叫数，用于测试结算
+    fn place_bid(engine: &mut GameEngine, seat: usize, bid: Bid) {
+        engine.state.current_bid = Some(bid.clone());
+        engine.state.bid_history.push((seat, Action::Bid(bid)));
+    }
+
+    fn table(kinds: &[PlayerKind]) -> GameConfig {
+        GameConfig {
+            players: kinds
+                .iter()
+                .enumerate()
+                .map(|(i, &kind)| PlayerConfig {
+                    name: format!("Seat {}", i),
+                    kind,
+                    bluff_rate: None,
+                    personality: None,
+                })
+                .collect(),
+            ..Default::default()
         }
     }
 
     #[test]
     fn test_start_game() {
         let mut engine = GameEngine::new();
-        let view = engine.start_game();
-        assert_eq!(view.human_dice.len(), 5);
-        assert_eq!(view.ai_dice_count, 5);
-        assert_eq!(view.phase, GamePhase::PlayerTurn);
+        let view = engine.start_game(GameConfig::default()).unwrap();
+        assert_eq!(view.seat, HUMAN);
+        assert_eq!(view.dice.len(), 5);
+        assert_eq!(view.seats[AI].dice_count, 5);
+        assert_eq!(view.phase, GamePhase::Turn(HUMAN));
         assert_eq!(view.current_round, 1);
         assert_eq!(view.max_rounds, 5);
     }
 
+    #[test]
+    fn test_start_game_rejects_bad_table() {
+        let mut engine = GameEngine::new();
+        assert!(engine.start_game(table(&[PlayerKind::Human])).is_err());
+        assert!(engine.start_game(table(&[PlayerKind::AI; 9])).is_err());
+    }
+
     #[test]
     fn test_player_bid_first() {
         let mut engine = GameEngine::new();
-        engine.start_game();
-        let result = engine.player_bid(2, 3);
+        engine.start_game(GameConfig::default()).unwrap();
+        let result = engine.player_bid(HUMAN, 2, 3, false);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_player_bid_invalid_face() {
         let mut engine = GameEngine::new();
-        engine.start_game();
-        let result = engine.player_bid(2, 7);
+        engine.start_game(GameConfig::default()).unwrap();
+        let result = engine.player_bid(HUMAN, 2, 7, false);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_player_bid_rejects_ai_seat() {
+        let mut engine = GameEngine::new();
+        engine.start_game(GameConfig::default()).unwrap();
+        assert!(engine.player_bid(AI, 2, 3, false).is_err());
+        assert!(engine.player_bid(7, 2, 3, false).is_err());
+    }
+
     #[test]
     fn test_player_challenge_no_bid() {
         let mut engine = GameEngine::new();
-        engine.start_game();
-        let result = engine.player_challenge();
+        engine.start_game(GameConfig::default()).unwrap();
+        let result = engine.player_challenge(HUMAN);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_resolve_challenge() {
         let mut engine = GameEngine::new();
-        engine.start_game();
-        engine.state.human_dice = vec![3, 3, 3, 2, 1];
-        engine.state.ai_dice = vec![3, 3, 5, 6, 1];
-        engine.state.current_bid = Some(Bid { count: 5, face: 3 });
+        engine.start_game(GameConfig::default()).unwrap();
+        engine.state.seats[HUMAN].dice = vec![3, 3, 3, 2, 1];
+        engine.state.seats[AI].dice = vec![3, 3, 5, 6, 1];
+        place_bid(&mut engine, AI, Bid::new(5, 3));
 
-        let result = engine.resolve_challenge(Player::Human);
+        let result = engine.resolve_challenge(HUMAN);
         assert_eq!(result.round, 1);
         assert_eq!(result.actual_count, 5);
-        assert_eq!(result.winner, Player::AI);
-        assert_eq!(result.loser, Player::Human);
+        assert_eq!(result.winner, AI);
+        assert_eq!(result.loser, HUMAN);
+    }
+
+    #[test]
+    fn test_resolve_challenge_wild_ones() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                wild_ones: true,
+                ..Default::default()
+            })
+            .unwrap();
+        engine.state.seats[HUMAN].dice = vec![3, 1, 2, 2, 6];
+        engine.state.seats[AI].dice = vec![3, 1, 5, 6, 4];
+        place_bid(&mut engine, AI, Bid::new(4, 3));
+
+        let result = engine.resolve_challenge(HUMAN);
+        assert_eq!(result.actual_count, 4);
+        assert_eq!(result.wild_count, 2);
+        assert_eq!(result.winner, AI);
+        assert_eq!(result.loser, HUMAN);
+    }
+
+    #[test]
+    fn test_resolve_challenge_pure_bid() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                wild_ones: true,
+                ..Default::default()
+            })
+            .unwrap();
+        engine.state.seats[HUMAN].dice = vec![3, 1, 2, 2, 6];
+        engine.state.seats[AI].dice = vec![3, 1, 5, 6, 4];
+        place_bid(&mut engine, AI, Bid::new_pure(3, 3));
+
+        let result = engine.resolve_challenge(HUMAN);
+        assert_eq!(result.actual_count, 2);
+        assert_eq!(result.wild_count, 0);
+        assert_eq!(result.winner, HUMAN);
+    }
+
+    #[test]
+    fn test_resolve_challenge_between_non_adjacent_seats() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(table(&[
+                PlayerKind::Human,
+                PlayerKind::Human,
+                PlayerKind::Human,
+            ]))
+            .unwrap();
+        engine.state.seats[0].dice = vec![2, 2, 2, 2, 2];
+        engine.state.seats[1].dice = vec![4, 4, 4, 4, 4];
+        engine.state.seats[2].dice = vec![6, 6, 6, 6, 6];
+
+        engine.player_bid(0, 3, 4, false).unwrap();
+        engine.player_bid(1, 6, 4, false).unwrap();
+        let view = engine.player_challenge(2).unwrap();
+
+        let result = view.last_round_result.unwrap();
+        assert_eq!(result.winner, 2);
+        assert_eq!(result.loser, 1);
+        assert_eq!(result.dice.len(), 3);
+    }
+
+    #[test]
+    fn test_turns_rotate_through_seats() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(table(&[
+                PlayerKind::Human,
+                PlayerKind::Human,
+                PlayerKind::Human,
+            ]))
+            .unwrap();
+
+        assert_eq!(engine.state.phase, GamePhase::Turn(0));
+        engine.player_bid(0, 1, 2, false).unwrap();
+        assert_eq!(engine.state.phase, GamePhase::Turn(1));
+        assert!(engine.player_bid(0, 2, 2, false).is_err());
+        engine.player_bid(1, 2, 2, false).unwrap();
+        assert_eq!(engine.state.phase, GamePhase::Turn(2));
+        engine.player_bid(2, 3, 2, false).unwrap();
+        assert_eq!(engine.state.phase, GamePhase::Turn(0));
+    }
+
+    #[test]
+    fn test_ai_seats_play_until_human_turn() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(table(&[PlayerKind::Human, PlayerKind::AI, PlayerKind::AI]))
+            .unwrap();
+
+        let view = engine.player_bid(HUMAN, 1, 2, false).unwrap();
+        match view.phase {
+            GamePhase::Turn(seat) => {
+                assert_eq!(seat, HUMAN);
+                assert_eq!(view.bid_history.len(), 3);
+            }
+            GamePhase::RoundOver(_) | GamePhase::GameOver { .. } => {
+                assert!(view.bid_history.len() <= 3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_ai_table_plays_a_round() {
+        let mut engine = GameEngine::new();
+        let view = engine.start_game(table(&[PlayerKind::AI; 4])).unwrap();
+        assert!(matches!(view.phase, GamePhase::RoundOver(_)));
+        assert!(view.bid_history.len() >= 2);
+    }
+
+    #[test]
+    fn test_master_table_plays_a_round() {
+        let mut engine = GameEngine::new();
+        let view = engine
+            .start_game(GameConfig {
+                difficulty: Difficulty::Master,
+                ..table(&[PlayerKind::AI; 3])
+            })
+            .unwrap();
+        assert!(matches!(view.phase, GamePhase::RoundOver(_)));
+    }
+
+    #[test]
+    fn test_seeded_master_search_ignores_the_clock() {
+        let mut config = GameConfig {
+            difficulty: Difficulty::Master,
+            seed: Some(3),
+            ..table(&[PlayerKind::AI; 3])
+        };
+        assert_eq!(GameEngine::search_budget(&config).time_limit, None);
+        config.seed = None;
+        assert_eq!(GameEngine::search_budget(&config), SearchBudget::default());
+    }
+
+    #[test]
+    fn test_player_bid_pure_requires_wild_ones() {
+        let mut engine = GameEngine::new();
+        engine.start_game(GameConfig::default()).unwrap();
+        assert!(engine.player_bid(HUMAN, 2, 3, true).is_err());
+    }
+
+    #[test]
+    fn test_resolve_spot_on_exact_wins_double() {
+        let mut engine = GameEngine::new();
+        engine.start_game(GameConfig::default()).unwrap();
+        engine.state.seats[HUMAN].dice = vec![3, 3, 3, 2, 1];
+        engine.state.seats[AI].dice = vec![3, 3, 5, 6, 1];
+        place_bid(&mut engine, AI, Bid::new(5, 3));
+
+        let result = engine.resolve_spot_on(HUMAN);
+        assert!(result.spot_on);
+        assert_eq!(result.winner, HUMAN);
+        assert_eq!(result.stake, SPOT_ON_STAKE);
+
+        engine.apply_round_result(&result);
+        assert_eq!(engine.state.seats[HUMAN].wins, SPOT_ON_STAKE);
+    }
+
+    #[test]
+    fn test_resolve_spot_on_miss_loses() {
+        let mut engine = GameEngine::new();
+        engine.start_game(GameConfig::default()).unwrap();
+        engine.state.seats[HUMAN].dice = vec![3, 3, 3, 2, 1];
+        engine.state.seats[AI].dice = vec![3, 3, 5, 6, 1];
+        place_bid(&mut engine, AI, Bid::new(4, 3));
+
+        let result = engine.resolve_spot_on(HUMAN);
+        assert_eq!(result.winner, AI);
+        assert_eq!(result.loser, HUMAN);
+        assert_eq!(result.stake, 1);
+    }
+
+    #[test]
+    fn test_player_spot_on_no_bid() {
+        let mut engine = GameEngine::new();
+        engine.start_game(GameConfig::default()).unwrap();
+        assert!(engine.player_spot_on(HUMAN).is_err());
     }
 
     #[test]
     fn test_round_result_keeps_five_dice() {
         let mut engine = GameEngine::new();
-        engine.start_game();
-        let result = make_result(1, Player::Human, Player::AI);
+        engine.start_game(GameConfig::default()).unwrap();
+        let result = make_result(1, HUMAN, AI);
+
+        engine.apply_round_result(&result);
+
+        assert_eq!(engine.state.seats[HUMAN].dice_count, 5);
+        assert_eq!(engine.state.seats[AI].dice_count, 5);
+        assert_eq!(engine.state.seats[HUMAN].wins, 1);
+        assert_eq!(engine.state.seats[AI].wins, 0);
+        assert!(matches!(engine.state.phase, GamePhase::RoundOver(_)));
+    }
+
+    #[test]
+    fn test_dice_loss_loser_loses_a_die() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                format: MatchFormat::DiceLoss,
+                ..Default::default()
+            })
+            .unwrap();
+        let result = make_result(1, HUMAN, AI);
+
+        engine.apply_round_result(&result);
+
+        assert_eq!(engine.state.seats[HUMAN].dice_count, 5);
+        assert_eq!(engine.state.seats[AI].dice_count, 4);
+        assert!(matches!(engine.state.phase, GamePhase::RoundOver(_)));
+
+        engine.next_round(HUMAN).unwrap();
+        assert_eq!(engine.state.seats[AI].dice.len(), 4);
+        assert_eq!(engine.state.seats[HUMAN].dice.len(), 5);
+    }
+
+    #[test]
+    fn test_dice_loss_spot_on_costs_stake() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                format: MatchFormat::DiceLoss,
+                ..Default::default()
+            })
+            .unwrap();
+        let mut result = make_result(1, AI, HUMAN);
+        result.spot_on = true;
+        result.stake = SPOT_ON_STAKE;
 
         engine.apply_round_result(&result);
 
-        assert_eq!(engine.state.human_dice_count, 5);
-        assert_eq!(engine.state.ai_dice_count, 5);
-        assert_eq!(engine.state.human_wins, 1);
-        assert_eq!(engine.state.ai_wins, 0);
+        assert_eq!(engine.state.seats[HUMAN].dice_count, 5 - SPOT_ON_STAKE);
+    }
+
+    #[test]
+    fn test_dice_loss_game_over_when_out_of_dice() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                format: MatchFormat::DiceLoss,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // 打满 max_rounds 也不会结束，直到一方输光骰子
+        engine.state.current_round = engine.state.max_rounds;
+        for round in 1..=5 {
+            assert!(!matches!(engine.state.phase, GamePhase::GameOver { .. }));
+            let result = make_result(round, AI, HUMAN);
+            engine.apply_round_result(&result);
+            if round < 5 {
+                engine.next_round(HUMAN).unwrap();
+            }
+        }
+
+        assert_eq!(engine.state.seats[HUMAN].dice_count, 0);
+        assert_eq!(engine.state.phase, GamePhase::GameOver { winner: Some(AI) });
+    }
+
+    #[test]
+    fn test_dice_loss_eliminated_seat_is_skipped() {
+        let mut engine = GameEngine::new();
+        let mut config = table(&[PlayerKind::Human, PlayerKind::Human, PlayerKind::Human]);
+        config.format = MatchFormat::DiceLoss;
+        engine.start_game(config).unwrap();
+        engine.state.seats[1].dice_count = 1;
+
+        engine.apply_round_result(&make_result(1, 0, 1));
         assert!(matches!(engine.state.phase, GamePhase::RoundOver(_)));
+        assert!(!engine.state.seats[1].is_active());
+
+        engine.next_round(0).unwrap();
+        assert!(engine.state.seats[1].dice.is_empty());
+        engine.player_bid(0, 1, 2, false).unwrap();
+        assert_eq!(engine.state.phase, GamePhase::Turn(2));
+    }
+
+    fn palifico_engine() -> GameEngine {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                wild_ones: true,
+                format: MatchFormat::DiceLoss,
+                palifico: true,
+                ..Default::default()
+            })
+            .unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_palifico_round_when_dropping_to_one_die() {
+        let mut engine = palifico_engine();
+        engine.state.seats[HUMAN].dice_count = 2;
+        engine.apply_round_result(&make_result(1, AI, HUMAN));
+        assert_eq!(engine.state.seats[HUMAN].dice_count, 1);
+
+        engine.next_round(HUMAN).unwrap();
+        assert_eq!(engine.state.palifico, Some(HUMAN));
+        assert!(!engine.state.wilds_active());
+        assert_eq!(engine.state.phase, GamePhase::Turn(HUMAN));
+
+        // 首个叫数锁定点数
+        engine.state.seats[AI].dice = vec![6, 6, 6, 6, 6];
+        engine.player_bid(HUMAN, 1, 2, false).unwrap();
+        assert_eq!(engine.state.locked_face(), Some(2));
+        if let Some((AI, Action::Bid(bid))) = engine.state.bid_history.get(1) {
+            assert_eq!(bid.face, 2);
+        }
+    }
+
+    #[test]
+    fn test_palifico_ai_opens() {
+        let mut engine = palifico_engine();
+        engine.state.seats[AI].dice_count = 2;
+        engine.apply_round_result(&make_result(1, HUMAN, AI));
+
+        engine.next_round(HUMAN).unwrap();
+        assert_eq!(engine.state.palifico, Some(AI));
+        assert_eq!(engine.state.phase, GamePhase::Turn(HUMAN));
+        assert!(engine.state.current_bid.is_some());
+        assert_eq!(engine.state.bid_history[0].0, AI);
+    }
+
+    #[test]
+    fn test_palifico_only_once() {
+        let mut engine = palifico_engine();
+        engine.state.seats[HUMAN].dice_count = 2;
+        engine.apply_round_result(&make_result(1, AI, HUMAN));
+        engine.next_round(HUMAN).unwrap();
+        assert_eq!(engine.state.palifico, Some(HUMAN));
+
+        // 赢下独骰局后恢复普通局
+        engine.apply_round_result(&make_result(2, HUMAN, AI));
+        engine.next_round(HUMAN).unwrap();
+        assert_eq!(engine.state.palifico, None);
+    }
+
+    #[test]
+    fn test_no_palifico_when_disabled() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                format: MatchFormat::DiceLoss,
+                ..Default::default()
+            })
+            .unwrap();
+        engine.state.seats[HUMAN].dice_count = 2;
+        engine.apply_round_result(&make_result(1, AI, HUMAN));
+        engine.next_round(HUMAN).unwrap();
+        assert_eq!(engine.state.palifico, None);
     }
 
     #[test]
     fn test_game_over_after_five_rounds() {
         let mut engine = GameEngine::new();
-        engine.start_game();
+        engine.start_game(GameConfig::default()).unwrap();
 
         for round in 1..=5 {
             engine.state.current_round = round;
-            let result = make_result(round, Player::Human, Player::AI);
+            let result = make_result(round, HUMAN, AI);
             engine.apply_round_result(&result);
 
             if round < 5 {
                 assert!(matches!(engine.state.phase, GamePhase::RoundOver(_)));
-                let _ = engine.next_round().unwrap();
+                let _ = engine.next_round(HUMAN).unwrap();
             }
         }
 
         assert!(matches!(engine.state.phase, GamePhase::GameOver { .. }));
-        assert_eq!(engine.state.human_wins, 5);
-        assert_eq!(engine.state.ai_wins, 0);
+        assert_eq!(engine.state.seats[HUMAN].wins, 5);
+        assert_eq!(engine.state.seats[AI].wins, 0);
         if let GamePhase::GameOver { winner } = engine.state.phase {
-            assert_eq!(winner, Player::Human);
+            assert_eq!(winner, Some(HUMAN));
+        }
+    }
+
+    #[test]
+    fn test_start_game_with_custom_rules() {
+        let mut engine = GameEngine::new();
+        let view = engine
+            .start_game(GameConfig {
+                dice_per_player: 3,
+                die_sides: 8,
+                max_rounds: 3,
+                reroll_straights: false,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(view.dice.len(), 3);
+        assert_eq!(view.max_rounds, 3);
+        assert!(engine.player_bid(HUMAN, 1, 8, false).is_ok());
+    }
+
+    #[test]
+    fn test_first_opener_opens_each_round() {
+        let mut engine = GameEngine::new();
+        let mut config = table(&[PlayerKind::Human, PlayerKind::Human, PlayerKind::Human]);
+        config.first_opener = 2;
+        let view = engine.start_game(config).unwrap();
+        assert_eq!(view.phase, GamePhase::Turn(2));
+
+        engine.apply_round_result(&make_result(1, 0, 1));
+        engine.next_round(0).unwrap();
+        assert_eq!(engine.state.phase, GamePhase::Turn(2));
+    }
+
+    /// 打满局数且 0 号与 1 号座位胜场并列，最后一局由 1 号座位赢得
+    fn tied_final_round(tie_break: TieBreak) -> GamePhase {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                tie_break,
+                ..Default::default()
+            })
+            .unwrap();
+        engine.state.current_round = engine.state.max_rounds;
+        engine.state.seats[HUMAN].wins = 2;
+        engine.state.seats[AI].wins = 1;
+        engine.apply_round_result(&make_result(5, AI, HUMAN));
+        engine.state.phase.clone()
+    }
+
+    #[test]
+    fn test_tie_break_seat_order() {
+        assert_eq!(
+            tied_final_round(TieBreak::SeatOrder),
+            GamePhase::GameOver {
+                winner: Some(HUMAN)
+            }
+        );
+    }
+
+    #[test]
+    fn test_tie_break_last_round_winner() {
+        assert_eq!(
+            tied_final_round(TieBreak::LastRoundWinner),
+            GamePhase::GameOver { winner: Some(AI) }
+        );
+    }
+
+    #[test]
+    fn test_tie_break_draw() {
+        assert_eq!(
+            tied_final_round(TieBreak::Draw),
+            GamePhase::GameOver { winner: None }
+        );
+    }
+
+    #[test]
+    fn test_tie_break_draw_is_default() {
+        assert_eq!(GameConfig::default().tie_break, TieBreak::Draw);
+        let mut engine = GameEngine::new();
+        engine.start_game(GameConfig::default()).unwrap();
+        engine.state.current_round = engine.state.max_rounds;
+        engine.state.seats[AI].wins = 2;
+        engine.state.seats[HUMAN].wins = 1;
+        // 人类赢下最后一局追平，不再默认判人类获胜
+        engine.apply_round_result(&make_result(5, HUMAN, AI));
+        assert_eq!(engine.state.phase, GamePhase::GameOver { winner: None });
+    }
+
+    #[test]
+    fn test_tie_break_sudden_death_plays_on() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                tie_break: TieBreak::SuddenDeath,
+                ..Default::default()
+            })
+            .unwrap();
+        engine.state.current_round = engine.state.max_rounds;
+        engine.state.seats[HUMAN].wins = 2;
+        engine.state.seats[AI].wins = 1;
+        engine.apply_round_result(&make_result(5, AI, HUMAN));
+        assert!(matches!(engine.state.phase, GamePhase::RoundOver(_)));
+
+        // 加赛局决出胜者
+        engine.next_round(HUMAN).unwrap();
+        assert_eq!(engine.state.current_round, 6);
+        engine.apply_round_result(&make_result(6, AI, HUMAN));
+        assert_eq!(engine.state.phase, GamePhase::GameOver { winner: Some(AI) });
+    }
+
+    #[test]
+    fn test_tie_break_most_dice() {
+        let mut engine = GameEngine::new();
+        let mut config = table(&[PlayerKind::Human, PlayerKind::AI, PlayerKind::AI]);
+        config.tie_break = TieBreak::MostDice;
+        engine.start_game(config).unwrap();
+        engine.state.current_round = engine.state.max_rounds;
+        engine.state.seats[0].wins = 1;
+        engine.state.seats[1].wins = 1;
+        engine.state.seats[2].wins = 2;
+        engine.state.seats[1].dice_count = 3;
+        engine.state.seats[2].dice_count = 4;
+        // 0 号座位骰子最多，但不在并列者之中
+        engine.apply_round_result(&make_result(5, 1, 0));
+        assert_eq!(engine.state.phase, GamePhase::GameOver { winner: Some(2) });
+    }
+
+    #[test]
+    fn test_tie_break_most_dice_level_is_draw() {
+        assert_eq!(
+            tied_final_round(TieBreak::MostDice),
+            GamePhase::GameOver { winner: None }
+        );
+    }
+
+    fn opener_engine(rule: OpenerRule) -> GameEngine {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                opener_rule: rule,
+                ..Default::default()
+            })
+            .unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_loser_opens_and_ai_bids_immediately() {
+        let mut engine = opener_engine(OpenerRule::LoserOpens);
+        assert_eq!(engine.state.opener, HUMAN);
+
+        engine.apply_round_result(&make_result(1, HUMAN, AI));
+        let view = engine.next_round(HUMAN).unwrap();
+        assert_eq!(view.opener, AI);
+        assert_eq!(view.phase, GamePhase::Turn(HUMAN));
+        assert!(view.current_bid.is_some());
+        assert_eq!(view.bid_history[0].0, AI);
+    }
+
+    #[test]
+    fn test_winner_opens() {
+        let mut engine = opener_engine(OpenerRule::WinnerOpens);
+        engine.apply_round_result(&make_result(1, HUMAN, AI));
+        engine.next_round(HUMAN).unwrap();
+        assert_eq!(engine.state.opener, HUMAN);
+        assert_eq!(engine.state.phase, GamePhase::Turn(HUMAN));
+        assert!(engine.state.current_bid.is_none());
+    }
+
+    #[test]
+    fn test_alternate_opener_rotates_seats() {
+        let mut engine = GameEngine::new();
+        let mut config = table(&[PlayerKind::Human; 3]);
+        config.opener_rule = OpenerRule::Alternate;
+        engine.start_game(config).unwrap();
+
+        for expected in [1, 2, 0, 1] {
+            engine.apply_round_result(&make_result(engine.state.current_round, 0, 1));
+            engine.next_round(0).unwrap();
+            assert_eq!(engine.state.opener, expected);
+            assert_eq!(engine.state.phase, GamePhase::Turn(expected));
+        }
+    }
+
+    #[test]
+    fn test_loser_opens_skips_eliminated_loser() {
+        let mut engine = GameEngine::new();
+        let mut config = table(&[PlayerKind::Human; 3]);
+        config.format = MatchFormat::DiceLoss;
+        config.opener_rule = OpenerRule::LoserOpens;
+        engine.start_game(config).unwrap();
+        engine.state.seats[1].dice_count = 1;
+
+        engine.apply_round_result(&make_result(1, 0, 1));
+        engine.next_round(0).unwrap();
+        assert!(!engine.state.seats[1].is_active());
+        assert_eq!(engine.state.opener, 2);
+    }
+
+    #[test]
+    fn test_random_opener_is_an_active_seat() {
+        let mut chosen = Vec::new();
+        for seed in 0..20 {
+            let mut engine = GameEngine::new();
+            let mut config = table(&[PlayerKind::Human; 4]);
+            config.opener_rule = OpenerRule::Random;
+            config.seed = Some(seed);
+            engine.start_game(config).unwrap();
+            assert_eq!(engine.state.phase, GamePhase::Turn(engine.state.opener));
+
+            // 座位 0 与 2 出局后，随机先叫只能落在仍有骰子的座位上
+            for seat in [0, 2] {
+                engine.state.seats[seat].dice.clear();
+                engine.state.seats[seat].dice_count = 0;
+            }
+            for _ in 0..5 {
+                let opener = engine.round_opener();
+                assert!(!engine.state.seats[opener].dice.is_empty());
+                chosen.push(opener);
+            }
+        }
+        assert!(chosen.contains(&1) && chosen.contains(&3));
+    }
+
+    #[test]
+    fn test_same_seed_replays_game() {
+        let play = || {
+            let mut engine = GameEngine::new();
+            let mut config = table(&[PlayerKind::AI; 3]);
+            config.seed = Some(7);
+            config.opener_rule = OpenerRule::Random;
+            let view = engine.start_game(config).unwrap();
+            // 对局中种子不进视角，否则可据此重算对手的骰子
+            assert_eq!((view.seed, view.config.seed), (None, None));
+
+            let mut log = Vec::new();
+            while let GamePhase::RoundOver(result) = engine.state.phase.clone() {
+                assert_eq!(result.seed, Some(7));
+                let view = engine.state.view_for(0);
+                assert_eq!(view.seed, None);
+                assert!(view.rounds.iter().all(|r| r.result.seed.is_none()));
+                assert!(matches!(view.phase, GamePhase::RoundOver(ref r) if r.seed.is_none()));
+                log.push((engine.state.bid_history.clone(), result));
+                engine.next_round(0).unwrap();
+            }
+            let view = engine.state.view_for(0);
+            assert_eq!(view.seed, Some(7));
+            assert!(view.rounds.iter().all(|r| r.result.seed == Some(7)));
+            (log, engine.state.phase.clone())
+        };
+        assert_eq!(play(), play());
+    }
+
+    #[test]
+    fn test_completed_rounds_survive_the_next_roll() {
+        let mut engine = GameEngine::new();
+        let dice = ScriptedDice::new([2, 2, 3, 3, 6, 6, 6, 4, 4, 5]);
+        engine
+            .start_game_with_dice(GameConfig::default(), Box::new(dice))
+            .unwrap();
+        engine.player_bid(HUMAN, 10, 6, false).unwrap();
+
+        let view = engine.next_round(HUMAN).unwrap();
+        assert!(view.bid_history.is_empty());
+        assert_eq!(view.rounds.len(), 1);
+        let first = &view.rounds[0];
+        assert_eq!(first.opener, HUMAN);
+        assert_eq!(
+            first.bid_history,
+            vec![
+                (HUMAN, Action::Bid(Bid::new(10, 6))),
+                (AI, Action::Challenge)
+            ]
+        );
+        assert_eq!(first.result.round, 1);
+        assert_eq!(first.result.dice[AI], vec![6, 6, 4, 4, 5]);
+
+        // 第二局不论谁先叫，都以人类开或叫到不可能的数量结束
+        if engine.state.current_bid.is_some() {
+            engine.player_challenge(HUMAN).unwrap();
+        } else {
+            engine.player_bid(HUMAN, 10, 6, false).unwrap();
+        }
+        let rounds = &engine.state.rounds;
+        assert_eq!(rounds.len(), 2);
+        assert_eq!(rounds[1].result.round, 2);
+        assert_eq!(rounds[0], view.rounds[0]);
+    }
+
+    #[test]
+    fn test_scripted_dice_drive_a_round() {
+        let mut engine = GameEngine::new();
+        let dice = ScriptedDice::new([2, 2, 3, 3, 6, 6, 6, 4, 4, 5]);
+        let view = engine
+            .start_game_with_dice(GameConfig::default(), Box::new(dice))
+            .unwrap();
+        assert_eq!(view.dice, vec![2, 2, 3, 3, 6]);
+        assert_eq!(engine.state.seats[AI].dice, vec![6, 6, 4, 4, 5]);
+        assert_eq!(view.seed, None);
+
+        // 叫到场上不可能的数量，AI 必然开
+        engine.player_bid(HUMAN, 10, 6, false).unwrap();
+        let GamePhase::RoundOver(result) = engine.state.phase.clone() else {
+            panic!("expected the AI to challenge");
+        };
+        assert_eq!(result.winner, AI);
+        assert_eq!(result.actual_count, 3);
+        assert_eq!(
+            result.bids,
+            vec![BidReview {
+                seat: HUMAN,
+                bid: Bid::new(10, 6),
+                held: 1,
+                bluff: false,
+                probability: 0.0,
+            }]
+        );
+        // AI 手中 2 个 6，剩下 5 颗骰子凑不出 8 个：开必赢，定必输
+        assert_eq!(
+            result.call,
+            CallReview {
+                seat: AI,
+                probability: 0.0,
+                exact_probability: 0.0,
+                expected_value: 1.0,
+                alternative_value: -1.0,
+            }
+        );
+    }
+
+    /// 总是给出同一行动的测试策略
+    struct Always(Action);
+
+    impl Strategy for Always {
+        fn decide(&mut self, _observation: &PlayerObservation) -> Action {
+            self.0.clone()
         }
     }
+
+    #[test]
+    fn test_set_strategy_swaps_ai_seat() {
+        let mut engine = GameEngine::new();
+        engine.start_game(GameConfig::default()).unwrap();
+        engine
+            .set_strategy(AI, Box::new(Always(Action::Challenge)))
+            .unwrap();
+
+        engine.player_bid(HUMAN, 1, 2, false).unwrap();
+        let GamePhase::RoundOver(result) = engine.state.phase.clone() else {
+            panic!("expected the swapped strategy to challenge");
+        };
+        assert_eq!(
+            engine.state.bid_history.last(),
+            Some(&(AI, Action::Challenge))
+        );
+        assert_eq!(result.last_bid, Bid::new(1, 2));
+    }
+
+    #[test]
+    fn test_set_strategy_rejects_human_and_missing_seats() {
+        let mut engine = GameEngine::new();
+        engine.start_game(GameConfig::default()).unwrap();
+        assert!(engine
+            .set_strategy(HUMAN, Box::new(Always(Action::Challenge)))
+            .is_err());
+        assert!(engine
+            .set_strategy(5, Box::new(Always(Action::Challenge)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_illegal_strategy_actions_fall_back() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                first_opener: AI,
+                ..Default::default()
+            })
+            .unwrap();
+        engine
+            .set_strategy(AI, Box::new(Always(Action::SpotOn)))
+            .unwrap();
+
+        // 没有叫数时定不合法，改为叫 1 个 1
+        engine.new_round();
+        assert_eq!(
+            engine.state.bid_history,
+            vec![(AI, Action::Bid(Bid::new(1, WILD_FACE)))]
+        );
+
+        // 不够大的叫数改为开
+        engine
+            .set_strategy(AI, Box::new(Always(Action::Bid(Bid::new(1, 2)))))
+            .unwrap();
+        engine.player_bid(HUMAN, 2, 2, false).unwrap();
+        assert_eq!(
+            engine.state.bid_history.last(),
+            Some(&(AI, Action::Challenge))
+        );
+    }
+
+    #[test]
+    fn test_strategy_bids_outside_the_table_fall_back() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game(GameConfig {
+                first_opener: AI,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // 开局叫 0 个改为叫 1 个 1
+        engine
+            .set_strategy(AI, Box::new(Always(Action::Bid(Bid::new(0, 3)))))
+            .unwrap();
+        engine.new_round();
+        assert_eq!(
+            engine.state.bid_history,
+            vec![(AI, Action::Bid(Bid::new(1, WILD_FACE)))]
+        );
+
+        // 超过场上骰子总数的叫数改为开
+        engine
+            .set_strategy(AI, Box::new(Always(Action::Bid(Bid::new(1000, 6)))))
+            .unwrap();
+        engine.player_bid(HUMAN, 2, 2, false).unwrap();
+        assert_eq!(
+            engine.state.bid_history.last(),
+            Some(&(AI, Action::Challenge))
+        );
+        assert!(engine.player_bid(HUMAN, 11, 6, false).is_err());
+    }
+
+    #[test]
+    fn test_hint_only_on_human_turn() {
+        let mut engine = GameEngine::new();
+        engine.start_game(GameConfig::default()).unwrap();
+        let hint = engine.hint(HUMAN).unwrap();
+        assert_eq!(hint.bid_probability, None);
+        assert!(matches!(hint.recommended, Action::Bid(_)));
+        assert!(engine.hint(AI).is_err());
+
+        engine
+            .set_strategy(AI, Box::new(Always(Action::Bid(Bid::new(2, 2)))))
+            .unwrap();
+        engine.player_bid(HUMAN, 1, 2, false).unwrap();
+        assert!(engine.hint(HUMAN).unwrap().bid_probability.is_some());
+    }
+
+    #[test]
+    fn test_personality_seat_taunts_each_action() {
+        let mut config = GameConfig::default();
+        config.players[AI].personality = Some(Personality::Reckless);
+        let mut engine = GameEngine::new();
+        let view = engine.start_game(config).unwrap();
+        assert_eq!(view.seats[AI].personality, Some(Personality::Reckless));
+        assert!(view.taunts.is_empty());
+
+        let view = engine.player_bid(HUMAN, 1, 2, false).unwrap();
+        let ai_actions: Vec<usize> = (0..view.bid_history.len())
+            .filter(|&i| view.bid_history[i].0 == AI)
+            .collect();
+        assert_eq!(ai_actions.len(), 1);
+        assert_eq!(view.taunts.len(), 1);
+        let taunt = &view.taunts[0];
+        assert_eq!((taunt.seat, taunt.action), (AI, ai_actions[0]));
+        let action = &view.bid_history[taunt.action].1;
+        assert_eq!(taunt.line, Personality::Reckless.line(action, taunt.action));
+    }
+
+    /// 记下每次决策时看到的人类玩家档案
+    struct ProfileSpy(std::sync::Arc<std::sync::Mutex<Vec<Option<PlayerProfile>>>>);
+
+    impl Strategy for ProfileSpy {
+        fn decide(&mut self, observation: &PlayerObservation) -> Action {
+            let profile = observation.profile(HUMAN).cloned();
+            self.0.lock().unwrap().push(profile);
+            Action::Challenge
+        }
+    }
+
+    #[test]
+    fn test_profiles_carry_across_games() {
+        let path =
+            std::env::temp_dir().join(format!("engine-profiles-{}.json", std::process::id()));
+        let mut engine = GameEngine::new();
+        engine.load_profiles(&path);
+        let dice = ScriptedDice::new([2, 2, 3, 3, 6, 6, 6, 4, 4, 5]);
+        engine
+            .start_game_with_dice(GameConfig::default(), Box::new(dice))
+            .unwrap();
+        engine.player_bid(HUMAN, 10, 5, false).unwrap();
+        assert_eq!(
+            engine.profile("You").map(|p| (p.bids, p.bluffs)),
+            Some((1, 1))
+        );
+
+        // 新开一场后档案仍在，并出现在 AI 的观察中
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        engine.start_game(GameConfig::default()).unwrap();
+        engine
+            .set_strategy(AI, Box::new(ProfileSpy(seen.clone())))
+            .unwrap();
+        engine.player_bid(HUMAN, 1, 2, false).unwrap();
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].as_ref().map(|p| p.rounds), Some(1));
+
+        // 每局结束时写回档案文件
+        let mut reloaded = GameEngine::new();
+        reloaded.load_profiles(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reloaded.profile("You").map(|p| p.rounds), Some(2));
+        assert_eq!(reloaded.profile_warning(), None);
+    }
+
+    #[test]
+    fn test_corrupt_profiles_are_backed_up_before_rewriting() {
+        let path =
+            std::env::temp_dir().join(format!("corrupt-profiles-{}.json", std::process::id()));
+        let corrupt = "{\"You\": {\"rounds\": 3,";
+        std::fs::write(&path, corrupt).unwrap();
+        let mut engine = GameEngine::new();
+        engine.load_profiles(&path);
+        assert!(engine.profile("You").is_none());
+        let backup = path.with_extension("json.bak");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), corrupt);
+
+        let dice = ScriptedDice::new([2, 2, 3, 3, 6, 6, 6, 4, 4, 5]);
+        let view = engine
+            .start_game_with_dice(GameConfig::default(), Box::new(dice))
+            .unwrap();
+        assert!(view.profile_warning.unwrap().contains("moved the file"));
+        engine.player_bid(HUMAN, 10, 5, false).unwrap();
+        let mut reloaded = GameEngine::new();
+        reloaded.load_profiles(&path);
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&backup).unwrap();
+        assert_eq!(reloaded.profile("You").map(|p| p.rounds), Some(1));
+    }
+
+    #[test]
+    fn test_failed_profile_save_is_reported() {
+        // 档案目录的位置被普通文件占用，写档必然失败
+        let blocker = std::env::temp_dir().join(format!("profiles-blocker-{}", std::process::id()));
+        std::fs::write(&blocker, "").unwrap();
+        let mut engine = GameEngine::new();
+        engine.load_profiles(blocker.join("profiles.json"));
+        assert_eq!(engine.profile_warning(), None);
+
+        let dice = ScriptedDice::new([2, 2, 3, 3, 6, 6, 6, 4, 4, 5]);
+        engine
+            .start_game_with_dice(GameConfig::default(), Box::new(dice))
+            .unwrap();
+        let view = engine.player_bid(HUMAN, 10, 5, false).unwrap();
+        std::fs::remove_file(&blocker).unwrap();
+        assert!(view
+            .profile_warning
+            .unwrap()
+            .starts_with("Could not save profiles"));
+    }
+
+    #[test]
+    fn test_difficulty_reported_in_view() {
+        let mut engine = GameEngine::new();
+        let view = engine
+            .start_game(GameConfig {
+                difficulty: Difficulty::Expert,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(view.difficulty, Difficulty::Expert);
+    }
 }
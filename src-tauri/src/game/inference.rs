@@ -0,0 +1,333 @@
+use crate::game::odds::binomial_coefficient;
+use crate::game::types::*;
+use rand::Rng;
+
+/// 默认的对手诚实度：叫数有七成按自己手中的点数来叫
+pub const DEFAULT_TRUTHFULNESS: f64 = 0.7;
+/// 一手骰子的组合数超过此值时不做推断，退回先验
+const MAX_ENUMERATED_HANDS: usize = 20_000;
+
+/// 对手一手骰子的后验分布。
+///
+/// 以点数个数组合（第 i 项为点数 i+1 的个数）为状态，先验为掷骰分布
+/// （顺子重掷时排除顺子），每观察到该对手的一次叫数就按诚实度模型更新。
+#[derive(Debug, Clone)]
+pub struct HandPosterior {
+    hands: Vec<(Vec<u32>, f64)>,
+}
+
+impl HandPosterior {
+    /// `dice` 颗骰子的先验；组合数过多时返回 `None`
+    pub fn prior(dice: u32, config: &GameConfig) -> Option<Self> {
+        let sides = config.die_sides as usize;
+        // 组合数为 C(dice + sides - 1, sides - 1)，先算出来，超限时不必枚举
+        let size = binomial_coefficient(dice + config.die_sides - 1, config.die_sides - 1);
+        if size > MAX_ENUMERATED_HANDS as f64 {
+            return None;
+        }
+        let mut compositions = Vec::new();
+        enumerate_compositions(
+            dice,
+            sides,
+            &mut Vec::with_capacity(sides),
+            &mut compositions,
+        );
+
+        let rerolled =
+            config.reroll_straights && dice >= MIN_STRAIGHT_DICE && dice == config.dice_per_player;
+        let hands = compositions
+            .into_iter()
+            .filter(|counts| !(rerolled && counts.iter().all(|&n| n <= 1)))
+            .map(|counts| {
+                let weight = multinomial(&counts);
+                (counts, weight)
+            })
+            .collect();
+        let mut posterior = HandPosterior { hands };
+        posterior.normalize();
+        Some(posterior)
+    }
+
+    /// 观察到该对手叫了 `bid` 后更新。
+    ///
+    /// 似然模型：以概率 `truthfulness` 按手中计入各点数的个数（加一平滑）
+    /// 成比例地选择点数，否则在所有点数中均匀乱叫
+    pub fn observe(&mut self, bid: &Bid, wilds_active: bool, truthfulness: f64) {
+        let sides = self.hands.first().map_or(0, |(counts, _)| counts.len()) as u32;
+        for (counts, weight) in &mut self.hands {
+            let support = |face| hits(counts, face, bid.pure, wilds_active) as f64 + 1.0;
+            let total: f64 = (1..=sides).map(support).sum();
+            let truthful = support(bid.face) / total;
+            *weight *= truthfulness * truthful + (1.0 - truthfulness) / sides as f64;
+        }
+        self.normalize();
+    }
+
+    /// 这手骰子中计入叫数的个数的分布，下标为个数
+    pub fn count_pmf(&self, bid: &Bid, wilds_active: bool) -> Vec<f64> {
+        let dice = self
+            .hands
+            .first()
+            .map_or(0, |(counts, _)| counts.iter().sum::<u32>());
+        let mut pmf = vec![0.0; dice as usize + 1];
+        for (counts, weight) in &self.hands {
+            pmf[hits(counts, bid.face, bid.pure, wilds_active) as usize] += weight;
+        }
+        pmf
+    }
+
+    /// 按后验抽取一手骰子（点数顺序无意义，按点数从小到大排列）
+    pub fn sample(&self, rng: &mut impl Rng) -> Vec<u32> {
+        let mut target = rng.gen::<f64>();
+        let counts = self
+            .hands
+            .iter()
+            .find(|(_, weight)| {
+                target -= weight;
+                target < 0.0
+            })
+            .or(self.hands.last())
+            .map_or(&[][..], |(counts, _)| counts);
+        counts
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &n)| std::iter::repeat_n(i as u32 + 1, n as usize))
+            .collect()
+    }
+
+    fn normalize(&mut self) {
+        let total: f64 = self.hands.iter().map(|(_, w)| w).sum();
+        if total > 0.0 {
+            for (_, weight) in &mut self.hands {
+                *weight /= total;
+            }
+        }
+    }
+}
+
+/// 根据本局叫数历史，推断观察者以外各对手的手牌。
+/// 只返回本局叫过数的对手；未叫数的对手没有信息，直接使用先验。
+/// `truthfulness` 为默认诚实度，有历史档案的对手以档案估计为准
+pub fn opponent_posteriors(
+    observation: &PlayerObservation,
+    truthfulness: f64,
+) -> Vec<(usize, HandPosterior)> {
+    let wilds_active = observation.wilds_active();
+    let mut posteriors: Vec<(usize, HandPosterior)> = Vec::new();
+    let mut first_bid = true;
+
+    for (bidder, action) in &observation.bid_history {
+        let Action::Bid(bid) = action else {
+            continue;
+        };
+        // 独骰局中首个叫数之后点数被锁定，后续叫数不含点数信息
+        let informative = observation.palifico.is_none() || first_bid;
+        let opening = first_bid;
+        first_bid = false;
+        if *bidder == observation.seat || !informative {
+            continue;
+        }
+
+        let index = match posteriors.iter().position(|(s, _)| s == bidder) {
+            Some(index) => index,
+            None => {
+                let dice = observation.seats[*bidder].dice_count;
+                let Some(prior) = HandPosterior::prior(dice, &observation.config) else {
+                    continue;
+                };
+                posteriors.push((*bidder, prior));
+                posteriors.len() - 1
+            }
+        };
+        // 有档案的玩家按其以往的诈叫比例估计诚实度
+        let truthfulness = observation
+            .profile(*bidder)
+            .map_or(truthfulness, |p| p.truthfulness(opening, truthfulness));
+        posteriors[index].1.observe(bid, wilds_active, truthfulness);
+    }
+
+    posteriors
+}
+
+/// 组合中计入叫数的个数，与 `GameState::counts_as` 规则一致
+fn hits(counts: &[u32], face: u32, pure: bool, wilds_active: bool) -> u32 {
+    let own = counts[face as usize - 1];
+    if wilds_active && !pure && face != WILD_FACE {
+        own + counts[WILD_FACE as usize - 1]
+    } else {
+        own
+    }
+}
+
+/// 把 `dice` 颗骰子分到 `sides` 个点数上的所有组合
+fn enumerate_compositions(dice: u32, sides: usize, prefix: &mut Vec<u32>, out: &mut Vec<Vec<u32>>) {
+    if prefix.len() + 1 == sides {
+        prefix.push(dice);
+        out.push(prefix.clone());
+        prefix.pop();
+        return;
+    }
+    for n in 0..=dice {
+        prefix.push(n);
+        enumerate_compositions(dice - n, sides, prefix, out);
+        prefix.pop();
+    }
+}
+
+/// 多项式系数 n! / (n_1! ... n_s!)：该组合对应的有序掷骰序列数
+fn multinomial(counts: &[u32]) -> f64 {
+    let mut result = 1.0;
+    let mut placed = 0;
+    for &n in counts {
+        for i in 1..=n {
+            placed += 1;
+            result *= placed as f64 / i as f64;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::profile::PlayerProfile;
+
+    const HUMAN: usize = 0;
+    const AI: usize = 1;
+
+    impl HandPosterior {
+        /// 某点数个数的期望
+        fn expected_count(&self, face: u32) -> f64 {
+            self.hands
+                .iter()
+                .map(|(counts, weight)| counts[face as usize - 1] as f64 * weight)
+                .sum()
+        }
+    }
+
+    /// AI 座位的视角，对手先后叫了 `bid` 与同点数加一
+    fn bid_twice(config: GameConfig, bid: Bid) -> PlayerObservation {
+        let mut observation = PlayerObservation::new(config, AI, vec![2, 3, 4, 6, 6]);
+        observation.bid_history = vec![
+            (HUMAN, Action::Bid(bid.clone())),
+            (AI, Action::Bid(Bid::new(bid.count, 6))),
+            (HUMAN, Action::Bid(Bid::new(bid.count + 1, bid.face))),
+        ];
+        observation
+    }
+
+    #[test]
+    fn test_prior_excludes_straights() {
+        let prior = HandPosterior::prior(5, &GameConfig::default()).unwrap();
+        let pmf = prior.count_pmf(&Bid::new(1, 3), false);
+        // 非顺子手牌共 7776 - 720 = 7056 种，其中不含 3 的有 5^5 - 120 种
+        assert!((pmf[0] - 3005.0 / 7056.0).abs() < 1e-12);
+        assert!((pmf.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_prior_gives_up_before_enumerating_large_hands() {
+        // 5 颗六面骰有 C(10, 5) = 252 种组合，去掉 6 种顺子
+        let prior = HandPosterior::prior(5, &GameConfig::default()).unwrap();
+        assert_eq!(prior.hands.len(), 246);
+
+        let config = GameConfig {
+            dice_per_player: 10,
+            die_sides: 12,
+            ..GameConfig::default()
+        };
+        assert!(HandPosterior::prior(10, &config).is_none());
+    }
+
+    #[test]
+    fn test_repeated_bids_raise_expected_count() {
+        let prior = HandPosterior::prior(5, &GameConfig::default()).unwrap();
+        let observation = bid_twice(GameConfig::default(), Bid::new(4, 5));
+
+        let posteriors = opponent_posteriors(&observation, DEFAULT_TRUTHFULNESS);
+        assert_eq!(posteriors.len(), 1);
+        let (seat, posterior) = &posteriors[0];
+        assert_eq!(*seat, HUMAN);
+        assert!(posterior.expected_count(5) > prior.expected_count(5) + 0.3);
+        assert!(posterior.expected_count(2) < prior.expected_count(2));
+    }
+
+    #[test]
+    fn test_zero_truthfulness_keeps_prior() {
+        let prior = HandPosterior::prior(5, &GameConfig::default()).unwrap();
+        let observation = bid_twice(GameConfig::default(), Bid::new(4, 5));
+
+        let posteriors = opponent_posteriors(&observation, 0.0);
+        let bid = Bid::new(1, 5);
+        let a = prior.count_pmf(&bid, false);
+        let b = posteriors[0].1.count_pmf(&bid, false);
+        for (x, y) in a.iter().zip(&b) {
+            assert!((x - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_profiled_bluffer_is_trusted_less() {
+        let observation = bid_twice(GameConfig::default(), Bid::new(4, 5));
+        let trusted = opponent_posteriors(&observation, DEFAULT_TRUTHFULNESS);
+
+        let mut profiled = observation.clone();
+        profiled.profiles[HUMAN] = Some(PlayerProfile {
+            bids: 40,
+            bluffs: 30,
+            ..Default::default()
+        });
+        let doubted = opponent_posteriors(&profiled, DEFAULT_TRUTHFULNESS);
+        assert!(doubted[0].1.expected_count(5) < trusted[0].1.expected_count(5) - 0.1);
+    }
+
+    #[test]
+    fn test_own_bids_are_ignored() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![2, 4, 4, 5, 6]);
+        observation
+            .bid_history
+            .push((AI, Action::Bid(Bid::new(2, 4))));
+        assert!(opponent_posteriors(&observation, DEFAULT_TRUTHFULNESS).is_empty());
+    }
+
+    #[test]
+    fn test_sample_follows_posterior() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let observation = bid_twice(GameConfig::default(), Bid::new(4, 5));
+        let posteriors = opponent_posteriors(&observation, DEFAULT_TRUTHFULNESS);
+        let posterior = &posteriors[0].1;
+        let mut rng = StdRng::seed_from_u64(3);
+        let trials = 20_000;
+        let mut fives = 0;
+        for _ in 0..trials {
+            let hand = posterior.sample(&mut rng);
+            assert_eq!(hand.len(), 5);
+            fives += hand.iter().filter(|&&d| d == 5).count();
+        }
+        let mean = fives as f64 / trials as f64;
+        assert!((mean - posterior.expected_count(5)).abs() < 0.03);
+    }
+
+    fn mean(pmf: &[f64]) -> f64 {
+        pmf.iter().enumerate().map(|(k, p)| k as f64 * p).sum()
+    }
+
+    #[test]
+    fn test_wild_bids_shift_wild_count() {
+        let config = GameConfig {
+            wild_ones: true,
+            ..Default::default()
+        };
+        let prior = HandPosterior::prior(5, &config).unwrap();
+        let observation = bid_twice(config, Bid::new(3, 4));
+
+        // 4 点与百搭 1 点合计的期望个数上升
+        let posteriors = opponent_posteriors(&observation, 1.0);
+        let bid = Bid::new(1, 4);
+        assert!(mean(&posteriors[0].1.count_pmf(&bid, true)) > mean(&prior.count_pmf(&bid, true)));
+        assert!(posteriors[0].1.expected_count(4) > prior.expected_count(4));
+    }
+}
@@ -0,0 +1,497 @@
+use crate::game::ai::bid_modes;
+use crate::game::inference::{opponent_posteriors, HandPosterior};
+use crate::game::odds::binomial_tail;
+use crate::game::strategy::Strategy;
+use crate::game::types::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::{Duration, Instant};
+
+/// 默认每次决策抽样的对手手牌组数
+pub const DEFAULT_ITERATIONS: u32 = 200;
+/// 默认每次决策的用时上限。引擎在持有对局锁时运行 AI，大桌上也不能让界面久等。
+/// 抽样组数因此随机器快慢变化，只用于未指定种子的交互对局；有种子的对局与测试用 `SearchBudget::fixed`
+pub const DEFAULT_TIME_LIMIT: Duration = Duration::from_millis(500);
+/// 推断对手手牌时假定的诚实度。对打测试中假定对手几乎总按手牌叫数效果最好
+const TRUTHFULNESS: f64 = 0.95;
+/// 模拟中的快速策略：叫数成立概率低于此值时开。
+/// 模拟中偏向多开，搜索更看重叫数本身能否成立
+const ROLLOUT_CHALLENGE: f64 = 0.7;
+/// 模拟中的快速策略：加注成立概率不低于此值才加注
+const ROLLOUT_RAISE: f64 = 0.45;
+
+/// 搜索预算：抽样达到 `iterations` 组或用时超过 `time_limit` 即停止，至少抽样一组
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchBudget {
+    pub iterations: u32,
+    pub time_limit: Option<Duration>,
+}
+
+impl SearchBudget {
+    /// 只按抽样组数停止、没有用时上限的预算：决策与机器快慢无关，固定种子时可重放
+    pub fn fixed(iterations: u32) -> Self {
+        SearchBudget {
+            iterations,
+            time_limit: None,
+        }
+    }
+}
+
+impl Default for SearchBudget {
+    fn default() -> Self {
+        SearchBudget {
+            iterations: DEFAULT_ITERATIONS,
+            time_limit: Some(DEFAULT_TIME_LIMIT),
+        }
+    }
+}
+
+/// 蒙特卡洛 AI。
+///
+/// 每次决策按叫数历史推断的后验抽取对手手牌，对每个候选行动用快速策略把本局
+/// 余下的叫数模拟到结算，选期望收益（赢得 +stake、输掉 −stake）最高的行动。
+/// 各候选共用同一组抽样，比较时方差更小
+pub struct MonteCarloAi {
+    budget: SearchBudget,
+    rng: StdRng,
+}
+
+impl MonteCarloAi {
+    pub fn new(budget: SearchBudget) -> Self {
+        MonteCarloAi {
+            budget,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// 固定抽样与模拟所用的随机种子；在用时上限内抽满 `iterations` 组时决策可重放
+    pub fn with_seed(self, seed: u64) -> Self {
+        MonteCarloAi {
+            rng: StdRng::seed_from_u64(seed),
+            ..self
+        }
+    }
+
+    /// 候选行动：有叫数时为开、定与各点数的最小加注（及再加一）；
+    /// 首叫时为各点数在期望个数附近的叫数
+    fn candidates(observation: &PlayerObservation) -> Vec<Action> {
+        let total = observation.total_dice();
+        let mut actions = Vec::new();
+        if observation.current_bid.is_some() {
+            actions.push(Action::Challenge);
+            actions.push(Action::SpotOn);
+        }
+
+        for face in 1..=observation.config.die_sides {
+            for pure in bid_modes(observation.wilds_active(), face) {
+                let counts = match &observation.current_bid {
+                    Some(current) => match min_raise(current, face, pure, total) {
+                        Some(count) => vec![count, count + 1],
+                        None => Vec::new(),
+                    },
+                    None => {
+                        let expected =
+                            expected_count(observation, &observation.dice, face, pure).round();
+                        let expected = expected.max(1.0) as u32;
+                        vec![expected.max(2) - 1, expected, expected + 1]
+                    }
+                };
+                for count in counts {
+                    let bid = Bid { count, face, pure };
+                    if count <= total && observation.validate_bid(bid.clone()).is_ok() {
+                        actions.push(Action::Bid(bid));
+                    }
+                }
+            }
+        }
+        actions.dedup();
+        actions
+    }
+
+    /// 抽取一组对手手牌：叫过数的对手按后验，其余按掷骰规则
+    fn sample_hands(
+        &mut self,
+        observation: &PlayerObservation,
+        posteriors: &[(usize, HandPosterior)],
+    ) -> Vec<Vec<u32>> {
+        (0..observation.seats.len())
+            .map(|i| {
+                if i == observation.seat {
+                    return observation.dice.clone();
+                }
+                let dice = observation.seats[i].dice_count;
+                match posteriors.iter().find(|(s, _)| *s == i) {
+                    Some((_, posterior)) => posterior.sample(&mut self.rng),
+                    None => self.roll_hand(&observation.config, dice),
+                }
+            })
+            .collect()
+    }
+
+    /// 按规则掷一手骰子，满手顺子重掷
+    fn roll_hand(&mut self, config: &GameConfig, dice: u32) -> Vec<u32> {
+        let rerolled =
+            config.reroll_straights && dice >= MIN_STRAIGHT_DICE && dice == config.dice_per_player;
+        loop {
+            let hand: Vec<u32> = (0..dice)
+                .map(|_| self.rng.gen_range(1..=config.die_sides))
+                .collect();
+            let mut faces = hand.clone();
+            faces.sort_unstable();
+            faces.dedup();
+            if !rerolled || faces.len() < hand.len() {
+                return hand;
+            }
+        }
+    }
+}
+
+impl Strategy for MonteCarloAi {
+    fn decide(&mut self, observation: &PlayerObservation) -> Action {
+        let candidates = Self::candidates(observation);
+        if candidates.len() <= 1 {
+            return candidates.into_iter().next().unwrap_or(Action::Challenge);
+        }
+
+        let posteriors = opponent_posteriors(observation, TRUTHFULNESS);
+        let started = Instant::now();
+        let mut totals = vec![0.0; candidates.len()];
+        for iteration in 0..self.budget.iterations.max(1) {
+            if iteration > 0
+                && self
+                    .budget
+                    .time_limit
+                    .is_some_and(|t| started.elapsed() >= t)
+            {
+                break;
+            }
+            let hands = self.sample_hands(observation, &posteriors);
+            for (action, total) in candidates.iter().zip(&mut totals) {
+                let mut table = Table::new(observation, hands.clone());
+                *total += table.play_out(action.clone(), &mut self.rng);
+            }
+        }
+
+        let best = (0..candidates.len())
+            .max_by(|&a, &b| totals[a].total_cmp(&totals[b]))
+            .unwrap_or(0);
+        candidates[best].clone()
+    }
+}
+
+/// 一组抽样手牌下本局余下部分的快速模拟
+struct Table<'a> {
+    observation: &'a PlayerObservation,
+    hands: Vec<Vec<u32>>,
+    locked_face: Option<u32>,
+    /// 当前叫数及其叫数者
+    current: Option<(usize, Bid)>,
+}
+
+impl<'a> Table<'a> {
+    fn new(observation: &'a PlayerObservation, hands: Vec<Vec<u32>>) -> Self {
+        let bidder = observation
+            .bid_history
+            .iter()
+            .rev()
+            .find_map(|(seat, action)| matches!(action, Action::Bid(_)).then_some(*seat));
+        Table {
+            observation,
+            hands,
+            locked_face: observation.locked_face(),
+            current: bidder.zip(observation.current_bid.clone()),
+        }
+    }
+
+    /// 观察者先执行 `first`，之后各座位按 `rollout_action` 行动直到结算，
+    /// 返回观察者的收益
+    fn play_out(&mut self, first: Action, rng: &mut StdRng) -> f64 {
+        let mut seat = self.observation.seat;
+        let mut action = first;
+        loop {
+            match action {
+                Action::Bid(bid) => {
+                    if self.observation.palifico.is_some() && self.locked_face.is_none() {
+                        self.locked_face = Some(bid.face);
+                    }
+                    self.current = Some((seat, bid));
+                    seat = self.next_seat(seat);
+                    action = self.rollout_action(seat, rng);
+                }
+                Action::Challenge => return self.settle(seat, false),
+                Action::SpotOn => return self.settle(seat, true),
+            }
+        }
+    }
+
+    fn next_seat(&self, seat: usize) -> usize {
+        let n = self.hands.len();
+        (1..=n)
+            .map(|offset| (seat + offset) % n)
+            .find(|&i| !self.hands[i].is_empty())
+            .unwrap_or(seat)
+    }
+
+    /// 模拟中的快速策略：只看自己的手牌，其余骰子按二项分布估计。
+    /// 当前叫数成立概率过低时开，否则做成立概率最高的最小加注
+    fn rollout_action(&self, seat: usize, rng: &mut StdRng) -> Action {
+        let observation = self.observation;
+        let hand = &self.hands[seat];
+        let total = observation.total_dice();
+        let Some((_, current)) = &self.current else {
+            return Action::Challenge;
+        };
+        let noise = rng.gen_range(-0.1..0.1);
+        if support(observation, hand, current) < ROLLOUT_CHALLENGE + noise {
+            return Action::Challenge;
+        }
+
+        let faces = match self.locked_face {
+            Some(face) => face..=face,
+            None => 1..=observation.config.die_sides,
+        };
+        let best = faces
+            .flat_map(|face| {
+                bid_modes(observation.wilds_active(), face)
+                    .into_iter()
+                    .map(move |pure| (face, pure))
+            })
+            .filter_map(|(face, pure)| {
+                let bid = Bid {
+                    count: min_raise(current, face, pure, total)?,
+                    face,
+                    pure,
+                };
+                let prob = support(observation, hand, &bid);
+                Some((bid, prob))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((bid, prob)) if prob >= ROLLOUT_RAISE + noise => Action::Bid(bid),
+            _ => Action::Challenge,
+        }
+    }
+
+    /// 结算开或定，返回观察者的收益
+    fn settle(&self, caller: usize, spot_on: bool) -> f64 {
+        let Some((bidder, bid)) = &self.current else {
+            return 0.0;
+        };
+        let actual: u32 = self
+            .hands
+            .iter()
+            .map(|hand| self.observation.count_in(hand, bid.face, bid.pure))
+            .sum();
+        let caller_wins = if spot_on {
+            actual == bid.count
+        } else {
+            actual < bid.count
+        };
+        let (winner, loser) = if caller_wins {
+            (caller, *bidder)
+        } else {
+            (*bidder, caller)
+        };
+        let stake = if spot_on && caller_wins {
+            SPOT_ON_STAKE as f64
+        } else {
+            1.0
+        };
+
+        let me = self.observation.seat;
+        if winner == me {
+            stake
+        } else if loser == me {
+            -stake
+        } else {
+            0.0
+        }
+    }
+}
+
+/// 已知 `hand`、其余骰子按均匀分布时，场上计入叫数的期望个数
+fn expected_count(observation: &PlayerObservation, hand: &[u32], face: u32, pure: bool) -> f64 {
+    let (unknown, p) = unknown_dice(observation, hand, face, pure);
+    observation.count_in(hand, face, pure) as f64 + unknown as f64 * p
+}
+
+/// 已知 `hand`、其余骰子按均匀分布时，叫数成立的概率
+fn support(observation: &PlayerObservation, hand: &[u32], bid: &Bid) -> f64 {
+    let held = observation.count_in(hand, bid.face, bid.pure);
+    let Some(needed) = bid.count.checked_sub(held).filter(|&n| n > 0) else {
+        return 1.0;
+    };
+    let (unknown, p) = unknown_dice(observation, hand, bid.face, bid.pure);
+    binomial_tail(unknown, needed, p)
+}
+
+/// 手牌以外的骰子数，及每颗计入叫数的概率
+fn unknown_dice(
+    observation: &PlayerObservation,
+    hand: &[u32],
+    face: u32,
+    pure: bool,
+) -> (u32, f64) {
+    let matching = if observation.wilds_active() && !pure && face != WILD_FACE {
+        2.0
+    } else {
+        1.0
+    };
+    (
+        observation.total_dice() - hand.len() as u32,
+        matching / observation.config.die_sides as f64,
+    )
+}
+
+/// 在 `current` 之上叫 `face`（`pure`）的最小合法个数
+fn min_raise(current: &Bid, face: u32, pure: bool, total: u32) -> Option<u32> {
+    (1..=total).find(|&count| current.is_valid_raise(&Bid { count, face, pure }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::ai::AiEngine;
+    use crate::game::dice::strategy_seed;
+    use crate::game::engine::GameEngine;
+
+    /// 两名 AI 对打 `matches` 场、交替座位，返回 `challenger` 赢得的局数占比。
+    /// 每场首局由默认 AI 开局，只统计换上策略后的各局。策略种子按座位派生，与骰子无关
+    fn head_to_head(
+        matches: u64,
+        rounds: u32,
+        challenger: impl Fn(u64) -> Box<dyn Strategy>,
+        baseline: impl Fn(u64) -> Box<dyn Strategy>,
+    ) -> f64 {
+        let (mut won, mut played) = (0, 0);
+        for m in 0..matches {
+            let seat = (m % 2) as usize;
+            let mut engine = GameEngine::new();
+            engine
+                .start_game(GameConfig {
+                    players: vec![PlayerConfig::ai("A"), PlayerConfig::ai("B")],
+                    max_rounds: rounds + 1,
+                    opener_rule: OpenerRule::Alternate,
+                    seed: Some(m),
+                    ..Default::default()
+                })
+                .unwrap();
+            engine
+                .set_strategy(seat, challenger(strategy_seed(m, seat)))
+                .unwrap();
+            engine
+                .set_strategy(1 - seat, baseline(strategy_seed(m, 1 - seat)))
+                .unwrap();
+            while engine.next_round(0).is_ok() {
+                if let GamePhase::RoundOver(result) = &engine.state.phase {
+                    played += 1;
+                    won += (result.winner == seat) as u32;
+                }
+            }
+        }
+        won as f64 / played as f64
+    }
+
+    const HUMAN: usize = 0;
+    const AI: usize = 1;
+
+    #[test]
+    fn test_candidates_are_legal() {
+        let mut observation = PlayerObservation::new(
+            GameConfig {
+                wild_ones: true,
+                ..Default::default()
+            },
+            AI,
+            vec![1, 3, 3, 4, 6],
+        );
+        let openings = MonteCarloAi::candidates(&observation);
+        assert!(openings.iter().all(|a| matches!(a, Action::Bid(_))));
+
+        observation.palifico = Some(HUMAN);
+        observation.current_bid = Some(Bid::new(2, 5));
+        observation
+            .bid_history
+            .push((HUMAN, Action::Bid(Bid::new(2, 5))));
+        let actions = MonteCarloAi::candidates(&observation);
+        assert!(actions.contains(&Action::Challenge));
+        for action in actions {
+            if let Action::Bid(bid) = action {
+                assert_eq!(bid.face, 5);
+                assert!(observation.validate_bid(bid).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_challenges_impossible_bid() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![1, 2, 3, 3, 4]);
+        observation.current_bid = Some(Bid::new(9, 6));
+        observation
+            .bid_history
+            .push((HUMAN, Action::Bid(Bid::new(9, 6))));
+
+        let mut ai = MonteCarloAi::new(SearchBudget::fixed(DEFAULT_ITERATIONS)).with_seed(1);
+        assert_eq!(ai.decide(&observation), Action::Challenge);
+    }
+
+    #[test]
+    fn test_sampled_hands_do_not_replay_the_deal() {
+        for seed in 0..20 {
+            let config = GameConfig {
+                players: vec![PlayerConfig::ai("A"), PlayerConfig::ai("B")],
+                seed: Some(seed),
+                ..Default::default()
+            };
+            let mut engine = GameEngine::new();
+            engine.start_game(config.clone()).unwrap();
+            // 直接用骰子种子时，抽到的第一手正是发到手中的骰子
+            let mut reused =
+                MonteCarloAi::new(SearchBudget::fixed(DEFAULT_ITERATIONS)).with_seed(seed);
+            assert_eq!(reused.roll_hand(&config, 5), engine.state.seats[0].dice);
+            for seat in 0..2 {
+                let mut ai = MonteCarloAi::new(SearchBudget::fixed(DEFAULT_ITERATIONS))
+                    .with_seed(strategy_seed(seed, seat));
+                assert_ne!(ai.roll_hand(&config, 5), engine.state.seats[seat].dice);
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_budget_is_time_bounded() {
+        let budget = SearchBudget::default();
+        assert_eq!(budget.iterations, DEFAULT_ITERATIONS);
+        assert_eq!(budget.time_limit, Some(DEFAULT_TIME_LIMIT));
+        assert_eq!(SearchBudget::fixed(50).time_limit, None);
+    }
+
+    #[test]
+    fn test_time_budget_stops_search() {
+        let mut observation =
+            PlayerObservation::new(GameConfig::default(), AI, vec![2, 2, 3, 5, 6]);
+        observation.current_bid = Some(Bid::new(3, 4));
+        observation
+            .bid_history
+            .push((HUMAN, Action::Bid(Bid::new(3, 4))));
+
+        let mut ai = MonteCarloAi::new(SearchBudget {
+            iterations: u32::MAX,
+            time_limit: Some(Duration::from_millis(20)),
+        });
+        let started = Instant::now();
+        ai.decide(&observation);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_monte_carlo_beats_heuristic() {
+        let share = head_to_head(
+            40,
+            10,
+            |m| Box::new(MonteCarloAi::new(SearchBudget::fixed(DEFAULT_ITERATIONS)).with_seed(m)),
+            |m| Box::new(AiEngine::new().with_seed(m)),
+        );
+        assert!(share > 0.55, "won {:.3} of rounds", share);
+    }
+}
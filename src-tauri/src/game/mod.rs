@@ -1,3 +1,12 @@
 pub mod ai;
+pub mod cfr;
+pub mod dice;
 pub mod engine;
+pub mod inference;
+pub mod mcts;
+pub mod odds;
+pub mod personality;
+pub mod profile;
+pub mod report;
+pub mod strategy;
 pub mod types;
@@ -0,0 +1,453 @@
+//! 叫数概率计算：已知自己的骰子与各对手的骰子数，求「至少 / 恰好 k 个 f 点」的概率。
+//!
+//! 支持百搭（1 点计入其他点数，斋叫除外）与满手顺子重掷规则。AI 的概率估计、
+//! 提示与复盘都建立在这里的分布之上
+
+use crate::game::types::*;
+use serde::{Deserialize, Serialize};
+
+/// 一次概率查询：从持有 `my_dice` 的玩家视角，对手骰子的点数未知
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OddsQuery {
+    pub bid: Bid,
+    pub my_dice: Vec<u32>,
+    /// 每名对手的骰子数
+    pub opponent_dice: Vec<u32>,
+    /// 规则：用到面数、百搭、顺子重掷与每人骰子数
+    #[serde(default)]
+    pub config: GameConfig,
+    /// 独骰局中百搭失效
+    #[serde(default)]
+    pub palifico: bool,
+}
+
+/// 叫数成立的概率
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Odds {
+    /// 场上至少有 `bid.count` 个
+    pub at_least: f64,
+    /// 场上恰好有 `bid.count` 个，即定的胜率
+    pub exactly: f64,
+}
+
+impl OddsQuery {
+    /// `seat` 在局中实际掌握的信息：自己的骰子与其余在场座位的骰子数
+    pub fn for_seat(state: &GameState, seat: usize, bid: &Bid) -> OddsQuery {
+        OddsQuery {
+            bid: bid.clone(),
+            my_dice: state.seats[seat].dice.clone(),
+            opponent_dice: state
+                .seats
+                .iter()
+                .enumerate()
+                .filter(|(i, s)| *i != seat && !s.dice.is_empty())
+                .map(|(_, s)| s.dice.len() as u32)
+                .collect(),
+            config: state.config.clone(),
+            palifico: state.palifico.is_some(),
+        }
+    }
+
+    /// 计算概率。查询可能直接来自前端，先校验规则与各手骰子数，避免超大的分布。
+    /// 座位由 `opponent_dice` 给出，不校验 `config` 中的玩家名单
+    pub fn odds(&self) -> Result<Odds, String> {
+        self.config.validate_rules()?;
+        if self.opponent_dice.len() >= MAX_PLAYERS {
+            return Err(format!("At most {} opponents", MAX_PLAYERS - 1));
+        }
+        if self.my_dice.len() > MAX_DICE_PER_PLAYER as usize
+            || self.opponent_dice.iter().any(|&d| d > MAX_DICE_PER_PLAYER)
+        {
+            return Err(format!("A seat holds at most {} dice", MAX_DICE_PER_PLAYER));
+        }
+        let total = self.my_dice.len() as u32 + self.opponent_dice.iter().sum::<u32>();
+        if !(1..=total).contains(&self.bid.count) {
+            return Err(format!("Count must be between 1 and {}", total));
+        }
+        let sides = self.config.die_sides;
+        if !(1..=sides).contains(&self.bid.face) {
+            return Err(format!("Face must be between 1 and {}", sides));
+        }
+        if self.my_dice.iter().any(|d| !(1..=sides).contains(d)) {
+            return Err(format!("Dice must be between 1 and {}", sides));
+        }
+        let wilds_active = self.config.wild_ones && !self.palifico;
+        if self.bid.pure && !wilds_active {
+            return Err("Pure bids require the wild ones rule".to_string());
+        }
+
+        let counts_as = |d: u32| {
+            d == self.bid.face
+                || (wilds_active && !self.bid.pure && d == WILD_FACE && self.bid.face != WILD_FACE)
+        };
+        let held = self.my_dice.iter().filter(|&&d| counts_as(d)).count() as u32;
+        let dist = self
+            .opponent_dice
+            .iter()
+            .map(|&dice| seat_count_pmf(&self.config, dice, wilds_active, &self.bid))
+            .fold(vec![1.0], |acc, seat| convolve(&acc, &seat));
+
+        Ok(Odds {
+            at_least: match self.bid.count.checked_sub(held) {
+                // 从 0.0 累加：空迭代器的 `sum` 得到 -0.0
+                Some(needed) => dist.iter().skip(needed as usize).fold(0.0, |a, p| a + p),
+                None => 1.0,
+            },
+            exactly: match self.bid.count.checked_sub(held) {
+                Some(needed) => dist.get(needed as usize).copied().unwrap_or(0.0),
+                None => 0.0,
+            },
+        })
+    }
+}
+
+/// 开的期望收益：叫数不成立时赢 1，成立时输 1
+pub fn challenge_value(probability: f64) -> f64 {
+    (1.0 - probability) - probability
+}
+
+/// 定的期望收益：恰好成立时赢 SPOT_ON_STAKE，否则输 1
+pub fn spot_on_value(exact: f64) -> f64 {
+    exact * SPOT_ON_STAKE as f64 - (1.0 - exact)
+}
+
+/// 一名对手的 `dice` 颗骰子中计入 `bid` 的个数的分布，下标为个数。
+/// 按掷骰规则精确计算，不含任何关于其手牌的推断
+pub fn seat_count_pmf(config: &GameConfig, dice: u32, wilds_active: bool, bid: &Bid) -> Vec<f64> {
+    // 只有满手骰子会因顺子重掷
+    let rerolled =
+        config.reroll_straights && dice >= MIN_STRAIGHT_DICE && dice == config.dice_per_player;
+    let matching = matching_faces(wilds_active, bid);
+    hand_count_pmf(dice, config.die_sides, matching, rerolled)
+}
+
+/// 一手 `dice` 颗 `sides` 面骰中落在 `matching` 个指定点数上的个数的分布。
+///
+/// 不重掷时为二项分布。重掷顺子时，手牌在所有非顺子序列上均匀分布：
+/// 设 s = sides、n = dice、m = matching，顺子序列共 P(s, n) 个，其中恰有 k 颗
+/// 命中的有 C(n, k)·P(m, k)·P(s−m, n−k) 个，于是
+/// P(X = k) = (C(n, k)·m^k·(s−m)^(n−k) − C(n, k)·P(m, k)·P(s−m, n−k)) / (s^n − P(s, n))
+pub fn hand_count_pmf(dice: u32, sides: u32, matching: u32, straights_rerolled: bool) -> Vec<f64> {
+    let p = matching as f64 / sides as f64;
+    if !straights_rerolled || dice > sides {
+        return (0..=dice).map(|k| binomial_pmf(dice, k, p)).collect();
+    }
+
+    let total = (sides as f64).powi(dice as i32);
+    let straights = permutations(sides, dice);
+    (0..=dice)
+        .map(|k| {
+            let c = binomial_coefficient(dice, k);
+            let all = c
+                * (matching as f64).powi(k as i32)
+                * ((sides - matching) as f64).powi((dice - k) as i32);
+            let distinct = c * permutations(matching, k) * permutations(sides - matching, dice - k);
+            (all - distinct) / (total - straights)
+        })
+        .collect()
+}
+
+/// 一手骰子中最多的点数的个数，百搭生效时 1 点计入其他点数
+pub fn best_count(dice: &[u32], sides: u32, wilds_active: bool) -> u32 {
+    let mut counts = vec![0; sides as usize];
+    for &d in dice {
+        counts[d as usize - 1] += 1;
+    }
+    best_of_counts(&counts, wilds_active)
+}
+
+/// 随机一手 `dice` 颗骰子的 `best_count` 的期望，按掷骰规则计入顺子重掷。
+/// 枚举各点数的个数组合，按多项式系数加权
+pub fn expected_best_count(config: &GameConfig, dice: u32, wilds_active: bool) -> f64 {
+    // 只有满手骰子会因顺子重掷
+    let rerolled =
+        config.reroll_straights && dice >= MIN_STRAIGHT_DICE && dice == config.dice_per_player;
+    let mut counts = vec![0; config.die_sides as usize];
+    let (mut weight, mut total) = (0.0, 0.0);
+    visit_counts(&mut counts, 0, dice, &mut |counts| {
+        if rerolled && counts.iter().all(|&c| c <= 1) {
+            return;
+        }
+        let ways = counts
+            .iter()
+            .fold(factorial(dice), |ways, &c| ways / factorial(c));
+        weight += ways;
+        total += ways * best_of_counts(counts, wilds_active) as f64;
+    });
+    total / weight
+}
+
+/// 下标 0 为 1 点的个数
+fn best_of_counts(counts: &[u32], wilds_active: bool) -> u32 {
+    let best = counts.iter().skip(1).copied().max().unwrap_or(0);
+    if wilds_active {
+        counts[0] + best
+    } else {
+        best.max(counts[0])
+    }
+}
+
+/// 枚举把 `remaining` 颗骰子分到 `counts[face..]` 各点数上的全部方式
+fn visit_counts(counts: &mut [u32], face: usize, remaining: u32, f: &mut dyn FnMut(&[u32])) {
+    if face + 1 == counts.len() {
+        counts[face] = remaining;
+        f(counts);
+        return;
+    }
+    for c in 0..=remaining {
+        counts[face] = c;
+        visit_counts(counts, face + 1, remaining - c, f);
+    }
+}
+
+fn factorial(n: u32) -> f64 {
+    (1..=n).map(f64::from).product()
+}
+
+/// 两个独立计数之和的分布
+pub fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            out[i + j] += x * y;
+        }
+    }
+    out
+}
+
+/// 计入叫数的点数个数：百搭生效时非斋的非 1 点为 2（该点与 1 点），否则为 1
+pub fn matching_faces(wilds_active: bool, bid: &Bid) -> u32 {
+    if wilds_active && !bid.pure && bid.face != WILD_FACE {
+        2
+    } else {
+        1
+    }
+}
+
+/// 二项分布概率质量函数：C(n, k) * p^k * (1-p)^(n-k)
+pub fn binomial_pmf(n: u32, k: u32, p: f64) -> f64 {
+    let coeff = binomial_coefficient(n, k);
+    coeff * p.powi(k as i32) * (1.0 - p).powi((n - k) as i32)
+}
+
+/// 二项分布尾概率 P(X >= k)，X ~ B(n, p)
+pub fn binomial_tail(n: u32, k: u32, p: f64) -> f64 {
+    if k == 0 {
+        return 1.0;
+    }
+    // P(X >= k) = 1 − Σ_{i<k} C(n, i) p^i (1−p)^(n−i)，逐项递推
+    let mut term = (1.0 - p).powi(n as i32);
+    let mut below = 0.0;
+    for i in 0..k.min(n + 1) {
+        below += term;
+        term *= (n - i) as f64 / (i + 1) as f64 * p / (1.0 - p);
+    }
+    (1.0 - below).max(0.0)
+}
+
+/// 组合数 C(n, k)
+pub fn binomial_coefficient(n: u32, k: u32) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    let k = k.min(n - k); // 优化：C(n,k) = C(n,n-k)
+    let mut result = 1.0;
+    for i in 0..k {
+        result *= (n - i) as f64;
+        result /= (i + 1) as f64;
+    }
+    result
+}
+
+/// 排列数 P(n, k) = n! / (n-k)!，k > n 时为 0
+pub fn permutations(n: u32, k: u32) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    (0..k).map(|i| (n - i) as f64).product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(bid: Bid, my_dice: Vec<u32>, opponent_dice: Vec<u32>) -> OddsQuery {
+        OddsQuery {
+            bid,
+            my_dice,
+            opponent_dice,
+            config: GameConfig::default(),
+            palifico: false,
+        }
+    }
+
+    #[test]
+    fn test_binomial_coefficient() {
+        assert!((binomial_coefficient(5, 2) - 10.0).abs() < 1e-9);
+        assert!((binomial_coefficient(10, 3) - 120.0).abs() < 1e-9);
+        assert!((binomial_coefficient(5, 0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_binomial_tail_matches_pmf_sum() {
+        for (n, k, p) in [
+            (10, 3, 1.0 / 6.0),
+            (5, 0, 0.5),
+            (4, 5, 1.0 / 3.0),
+            (8, 8, 0.25),
+        ] {
+            let sum: f64 = (k..=n).map(|i| binomial_pmf(n, i, p)).sum();
+            assert!(
+                (binomial_tail(n, k, p) - sum).abs() < 1e-12,
+                "n={} k={}",
+                n,
+                k
+            );
+        }
+    }
+
+    #[test]
+    fn test_hand_count_pmf_matches_enumeration() {
+        // 枚举全部 6^5 手，去掉顺子后统计命中个数
+        for matching in [1, 2] {
+            let mut counts = [0u32; 6];
+            let mut hands = 0u32;
+            for code in 0..6u32.pow(5) {
+                let hand: Vec<u32> = (0..5).map(|i| code / 6u32.pow(i) % 6 + 1).collect();
+                let mut faces = hand.clone();
+                faces.sort();
+                faces.dedup();
+                if faces.len() == 5 {
+                    continue;
+                }
+                hands += 1;
+                counts[hand.iter().filter(|&&d| d <= matching).count()] += 1;
+            }
+
+            let pmf = hand_count_pmf(5, 6, matching, true);
+            for k in 0..=5 {
+                let expected = counts[k] as f64 / hands as f64;
+                assert!((pmf[k] - expected).abs() < 1e-12, "m={} k={}", matching, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hand_count_pmf_sums_to_one() {
+        for (dice, sides, matching, rerolled) in [
+            (5, 6, 1, true),
+            (5, 6, 2, true),
+            (3, 8, 2, true),
+            (7, 6, 1, true),
+            (4, 6, 1, false),
+        ] {
+            let total: f64 = hand_count_pmf(dice, sides, matching, rerolled).iter().sum();
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_expected_best_count_matches_enumeration() {
+        let config = GameConfig::default();
+        for wilds_active in [false, true] {
+            let mut total = 0u32;
+            let mut hands = 0u32;
+            for code in 0..6u32.pow(5) {
+                let hand: Vec<u32> = (0..5).map(|i| code / 6u32.pow(i) % 6 + 1).collect();
+                let mut faces = hand.clone();
+                faces.sort();
+                faces.dedup();
+                if faces.len() == 5 {
+                    continue;
+                }
+                hands += 1;
+                total += best_count(&hand, 6, wilds_active);
+            }
+            let expected = expected_best_count(&config, 5, wilds_active);
+            assert!((expected - total as f64 / hands as f64).abs() < 1e-12);
+        }
+
+        // 两颗骰子六分之一是对子；点数各不相同不算顺子，开着重掷规则也不重掷
+        let two = GameConfig {
+            dice_per_player: 2,
+            ..GameConfig::default()
+        };
+        assert!((expected_best_count(&two, 2, false) - 7.0 / 6.0).abs() < 1e-12);
+        assert!((expected_best_count(&config, 2, false) - 7.0 / 6.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_odds_count_held_dice_and_wilds() {
+        let mut q = query(Bid::new(3, 4), vec![1, 4, 4, 6, 6], vec![]);
+        // 没有百搭时只有 2 个 4
+        assert_eq!(
+            q.odds().unwrap(),
+            Odds {
+                at_least: 0.0,
+                exactly: 0.0
+            }
+        );
+
+        q.config.wild_ones = true;
+        assert_eq!(
+            q.odds().unwrap(),
+            Odds {
+                at_least: 1.0,
+                exactly: 1.0
+            }
+        );
+
+        // 斋叫与独骰局中 1 点不计入
+        q.bid = Bid::new_pure(3, 4);
+        assert_eq!(q.odds().unwrap().at_least, 0.0);
+        q.bid = Bid::new(3, 4);
+        q.palifico = true;
+        assert_eq!(q.odds().unwrap().at_least, 0.0);
+    }
+
+    #[test]
+    fn test_odds_follow_straight_reroll_rule() {
+        // 自己 1 个 3，需要对手 5 颗骰子中至少 1 个 3：顺子必含 3，被重掷后概率变低
+        let q = query(Bid::new(2, 3), vec![3, 2, 2, 4, 4], vec![5]);
+        let odds = q.odds().unwrap();
+        let pmf = hand_count_pmf(5, 6, 1, true);
+        assert!((odds.at_least - (1.0 - pmf[0])).abs() < 1e-12);
+        assert!((odds.exactly - pmf[1]).abs() < 1e-12);
+        assert!(odds.at_least < 1.0 - (5.0f64 / 6.0).powi(5));
+
+        // 少了骰子的对手不会重掷
+        let short = query(Bid::new(2, 3), vec![3], vec![4]).odds().unwrap();
+        assert!((short.at_least - (1.0 - (5.0f64 / 6.0).powi(4))).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_odds_reject_invalid_queries() {
+        assert!(query(Bid::new(2, 7), vec![1, 2], vec![5]).odds().is_err());
+        assert!(query(Bid::new(2, 3), vec![0, 2], vec![5]).odds().is_err());
+        assert!(query(Bid::new_pure(2, 3), vec![1, 2], vec![5])
+            .odds()
+            .is_err());
+        assert!(query(Bid::new(2, 3), vec![1, 2], vec![1_000_000_000])
+            .odds()
+            .is_err());
+        assert!(query(Bid::new(2, 3), vec![1, 2], vec![1; MAX_PLAYERS])
+            .odds()
+            .is_err());
+        assert!(query(Bid::new(0, 3), vec![1, 2], vec![5]).odds().is_err());
+        assert!(query(Bid::new(8, 3), vec![1, 2], vec![5]).odds().is_err());
+
+        let mut bad_rules = query(Bid::new(2, 3), vec![1, 2], vec![5]);
+        bad_rules.config.die_sides = 1000;
+        assert!(bad_rules.odds().is_err());
+
+        // 玩家名单与局数不影响概率，空名单也可以查询
+        let mut standalone = query(Bid::new(2, 3), vec![1, 2], vec![5]);
+        standalone.config.players.clear();
+        standalone.config.max_rounds = 0;
+        assert!(standalone.odds().is_ok());
+
+        // 凑不出的叫数概率为 +0.0
+        let unreachable = query(Bid::new(7, 3), vec![1, 2], vec![5]).odds().unwrap();
+        assert!(unreachable.at_least == 0.0 && unreachable.at_least.is_sign_positive());
+    }
+}
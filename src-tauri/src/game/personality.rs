@@ -0,0 +1,105 @@
+use crate::game::types::*;
+use serde::{Deserialize, Serialize};
+
+/// 供选择界面展示的性格说明
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PersonalityInfo {
+    pub personality: Personality,
+    pub name: String,
+    pub description: String,
+}
+
+impl Personality {
+    pub const ALL: [Personality; 4] = [
+        Personality::Cautious,
+        Personality::Reckless,
+        Personality::Mathematician,
+        Personality::Bluffer,
+    ];
+
+    pub fn info(self) -> PersonalityInfo {
+        let (name, description) = match self {
+            Personality::Cautious => (
+                "The Cautious",
+                "Calls early, only bids what it can back up, never bluffs.",
+            ),
+            Personality::Reckless => (
+                "The Reckless",
+                "Jumps bids high, calls late and bluffs often.",
+            ),
+            Personality::Mathematician => (
+                "The Mathematician",
+                "Plays the odds and weighs every raise against a challenge.",
+            ),
+            Personality::Bluffer => (
+                "The Bluffer",
+                "Sensible thresholds, but half of its raises are bluffs.",
+            ),
+        };
+        PersonalityInfo {
+            personality: self,
+            name: name.to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    /// 行动时的台词。每类行动有几句，按本局第 `turn` 个行动轮换
+    pub fn line(self, action: &Action, turn: usize) -> &'static str {
+        let lines: &[&str] = match (self, action) {
+            (Personality::Cautious, Action::Bid(_)) => {
+                &["Nothing I can't cover.", "A modest step."]
+            }
+            (Personality::Cautious, Action::Challenge) => {
+                &["That's too rich for me. Show me.", "I'd rather check."]
+            }
+            (Personality::Cautious, Action::SpotOn) => &["I believe it's exactly right."],
+            (Personality::Reckless, Action::Bid(_)) => &[
+                "Go big or go home!",
+                "Higher! Always higher!",
+                "Try and stop me.",
+            ],
+            (Personality::Reckless, Action::Challenge) => &["Liar! Lift those cups!"],
+            (Personality::Reckless, Action::SpotOn) => &["Dead on, I can feel it!"],
+            (Personality::Mathematician, Action::Bid(_)) => {
+                &["The numbers support it.", "Expected value says raise."]
+            }
+            (Personality::Mathematician, Action::Challenge) => {
+                &["Statistically implausible.", "The odds are against you."]
+            }
+            (Personality::Mathematician, Action::SpotOn) => &["That is the most likely count."],
+            (Personality::Bluffer, Action::Bid(_)) => {
+                &["Trust me.", "Would I lie to you?", "Read 'em and weep."]
+            }
+            (Personality::Bluffer, Action::Challenge) => &["Takes one to know one."],
+            (Personality::Bluffer, Action::SpotOn) => &["Exactly. Don't ask how I know."],
+        };
+        lines[turn % lines.len()]
+    }
+}
+
+/// 全部可选性格
+pub fn personalities() -> Vec<PersonalityInfo> {
+    Personality::ALL.iter().map(|p| p.info()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_personality_has_lines_for_every_action() {
+        let actions = [
+            Action::Bid(Bid::new(2, 3)),
+            Action::Challenge,
+            Action::SpotOn,
+        ];
+        for personality in Personality::ALL {
+            for action in &actions {
+                for turn in 0..4 {
+                    assert!(!personality.line(action, turn).is_empty());
+                }
+            }
+        }
+        assert_eq!(personalities().len(), 4);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::game::odds::matching_faces;
+use crate::game::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 估计倾向时先验的强度（相当于多少次观察）：样本少时向 AI 的默认假设收缩
+const PRIOR_WEIGHT: f64 = 10.0;
+/// 开的次数少于此值时不估计开的门槛
+const MIN_CHALLENGES: u32 = 3;
+
+/// 一名人类玩家跨场累积的习惯统计，按玩家名存档
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PlayerProfile {
+    /// 观察到的局数
+    pub rounds: u32,
+    pub bids: u32,
+    /// 叫了自己手中一个也没有的点数
+    pub bluffs: u32,
+    /// 本局首个叫数（开局叫数）的次数
+    pub openings: u32,
+    pub opening_bluffs: u32,
+    /// 开局叫数超出公开期望个数的累计量
+    pub opening_margin_sum: f64,
+    pub challenges: u32,
+    /// 开时被开叫数超出其自身视角期望个数的累计量
+    pub challenge_margin_sum: f64,
+}
+
+impl PlayerProfile {
+    /// 按已公开骰子的一局记录 `seat` 的叫数与开
+    pub fn record_round(&mut self, state: &GameState, seat: usize) {
+        let total: u32 = state.seats.iter().map(|s| s.dice.len() as u32).sum();
+        let dice = &state.seats[seat].dice;
+        let mut current: Option<&Bid> = None;
+        let mut opening = true;
+        self.rounds += 1;
+
+        for (bidder, action) in &state.bid_history {
+            match action {
+                Action::Bid(bid) => {
+                    if *bidder == seat {
+                        let held = state.count_in(dice, bid.face, bid.pure);
+                        self.bids += 1;
+                        self.bluffs += u32::from(held == 0);
+                        if opening {
+                            self.openings += 1;
+                            self.opening_bluffs += u32::from(held == 0);
+                            self.opening_margin_sum +=
+                                bid_margin(bid, 0, total, face_share(state, bid));
+                        }
+                    }
+                    current = Some(bid);
+                    opening = false;
+                }
+                Action::Challenge if *bidder == seat => {
+                    let Some(bid) = current else { continue };
+                    let held = state.count_in(dice, bid.face, bid.pure);
+                    let unknown = total - dice.len() as u32;
+                    self.challenges += 1;
+                    self.challenge_margin_sum +=
+                        bid_margin(bid, held, unknown, face_share(state, bid));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 该玩家叫数的诚实度估计，用于从其叫数推断手牌。
+    /// 以「没叫诈」的比例近似，并以 `default` 为先验收缩；开局叫数单独统计
+    pub fn truthfulness(&self, opening: bool, default: f64) -> f64 {
+        let (bids, bluffs) = if opening {
+            (self.openings, self.opening_bluffs)
+        } else {
+            // 档案来自文件，计数不一定自洽
+            (
+                self.bids.saturating_sub(self.openings),
+                self.bluffs.saturating_sub(self.opening_bluffs),
+            )
+        };
+        let honest = bids.saturating_sub(bluffs) as f64;
+        (PRIOR_WEIGHT * default + honest) / (PRIOR_WEIGHT + bids as f64)
+    }
+
+    /// 该玩家开的叫数平均超出其期望个数多少；样本太少时为 `None`
+    pub fn challenge_margin(&self) -> Option<f64> {
+        (self.challenges >= MIN_CHALLENGES)
+            .then(|| self.challenge_margin_sum / self.challenges as f64)
+    }
+
+    /// 开局叫数平均超出公开期望个数多少，体现开局的激进程度
+    pub fn opening_margin(&self) -> Option<f64> {
+        (self.openings > 0).then(|| self.opening_margin_sum / self.openings as f64)
+    }
+}
+
+/// 叫数超出期望的个数：已知 `held` 个，另有 `unknown` 颗未知骰子、
+/// 每颗以 `share` 的概率计入
+pub fn bid_margin(bid: &Bid, held: u32, unknown: u32, share: f64) -> f64 {
+    bid.count as f64 - held as f64 - unknown as f64 * share
+}
+
+/// 一颗未知骰子计入 `bid` 的概率
+fn face_share(state: &GameState, bid: &Bid) -> f64 {
+    matching_faces(state.wilds_active(), bid) as f64 / state.config.die_sides as f64
+}
+
+/// 各人类玩家的档案，跨场存于 JSON 文件
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(transparent)]
+pub struct ProfileBook {
+    profiles: HashMap<String, PlayerProfile>,
+}
+
+impl ProfileBook {
+    /// 读取档案；文件不存在时为空档案
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(ProfileBook::default());
+        }
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    /// 先写临时文件再改名覆盖，写到一半中断也不会留下损坏的档案
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, text).map_err(|e| e.to_string())?;
+        std::fs::rename(&temp, path).map_err(|e| e.to_string())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PlayerProfile> {
+        self.profiles.get(name)
+    }
+
+    /// 局末骰子公开后，为桌上本局在场的每个人类座位记录一局
+    pub fn record_round(&mut self, state: &GameState) {
+        for (seat, player) in state.seats.iter().enumerate() {
+            if player.kind == PlayerKind::Human && !player.dice.is_empty() {
+                self.profiles
+                    .entry(player.name.clone())
+                    .or_default()
+                    .record_round(state, seat);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HUMAN: usize = 0;
+    const AI: usize = 1;
+
+    /// 人类先叫了手中没有的 4，之后叫 5，最后开了 AI 的 4 个 5
+    fn finished_round() -> GameState {
+        let mut state = GameState::new();
+        state.seats[HUMAN].dice = vec![5, 2, 2, 3, 3];
+        state.seats[AI].dice = vec![5, 5, 1, 6, 6];
+        state.bid_history = vec![
+            (HUMAN, Action::Bid(Bid::new(1, 4))),
+            (AI, Action::Bid(Bid::new(2, 4))),
+            (HUMAN, Action::Bid(Bid::new(3, 5))),
+            (AI, Action::Bid(Bid::new(4, 5))),
+            (HUMAN, Action::Challenge),
+        ];
+        state
+    }
+
+    #[test]
+    fn test_record_round_counts_bluffs_and_challenges() {
+        let mut profile = PlayerProfile::default();
+        profile.record_round(&finished_round(), HUMAN);
+
+        assert_eq!(profile.rounds, 1);
+        assert_eq!(profile.bids, 2);
+        assert_eq!(profile.bluffs, 1);
+        assert_eq!(profile.openings, 1);
+        assert_eq!(profile.opening_bluffs, 1);
+        // 1 个 4，10 颗骰子期望 10/6 个
+        assert!((profile.opening_margin_sum - (1.0 - 10.0 / 6.0)).abs() < 1e-12);
+        // 4 个 5，自己有 1 个，另 5 颗骰子期望 5/6 个
+        assert_eq!(profile.challenges, 1);
+        assert!((profile.challenge_margin_sum - (3.0 - 5.0 / 6.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_estimates_shrink_toward_default() {
+        let mut profile = PlayerProfile::default();
+        assert_eq!(profile.truthfulness(false, 0.7), 0.7);
+        assert_eq!(profile.challenge_margin(), None);
+
+        profile.bids = 30;
+        profile.bluffs = 30;
+        assert!((profile.truthfulness(false, 0.7) - 7.0 / 40.0).abs() < 1e-12);
+        // 开局叫数没有记录，仍为默认值
+        assert_eq!(profile.truthfulness(true, 0.7), 0.7);
+
+        profile.challenges = 3;
+        profile.challenge_margin_sum = 4.5;
+        assert_eq!(profile.challenge_margin(), Some(1.5));
+    }
+
+    #[test]
+    fn test_inconsistent_counts_do_not_underflow() {
+        let profile = PlayerProfile {
+            bids: 1,
+            bluffs: 3,
+            openings: 2,
+            opening_bluffs: 5,
+            ..Default::default()
+        };
+        assert!((0.0..=1.0).contains(&profile.truthfulness(false, 0.7)));
+        assert!((0.0..=1.0).contains(&profile.truthfulness(true, 0.7)));
+    }
+
+    #[test]
+    fn test_book_records_humans_and_round_trips() {
+        let mut book = ProfileBook::default();
+        book.record_round(&finished_round());
+        assert_eq!(book.get("You").map(|p| p.bids), Some(2));
+        assert!(book.get("AI").is_none());
+
+        let path = std::env::temp_dir().join(format!("profiles-{}.json", std::process::id()));
+        book.save(&path).unwrap();
+        assert!(!path.with_extension("json.tmp").exists());
+        let loaded = ProfileBook::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            loaded.get("You").map(|p| (p.bids, p.challenges)),
+            Some((2, 1))
+        );
+        assert_eq!(ProfileBook::load(&path).unwrap(), ProfileBook::default());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::game::odds::{best_count, expected_best_count};
+use crate::game::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 整场结束后的复盘
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MatchReport {
+    /// `None` 为平局
+    pub winner: Option<usize>,
+    /// 各局的行动、结算与复盘
+    pub rounds: Vec<CompletedRound>,
+    /// 按座位号排列
+    pub players: Vec<PlayerReport>,
+    /// 全场按叫数者自己的信息成立概率最低的叫数
+    pub riskiest_bid: Option<RiskyBid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RiskyBid {
+    pub round: u32,
+    pub review: BidReview,
+}
+
+/// 一名玩家全场的决策统计。「技术」看决策当时的期望，「运气」看结果与骰子偏离期望多少
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PlayerReport {
+    pub seat: usize,
+    pub name: String,
+    pub bids: u32,
+    /// 叫了自己手中一个也没有的点数
+    pub bluffs: u32,
+    pub bluff_rate: Option<f64>,
+    /// 叫数成立概率的平均值，越低叫得越冒险
+    pub average_bid_probability: Option<f64>,
+    /// 开与定的次数，及其中叫对的次数
+    pub calls: u32,
+    pub correct_calls: u32,
+    pub call_accuracy: Option<f64>,
+    /// 被开或被定的叫数，及其中叫数者赢下的次数
+    pub bids_called: u32,
+    pub bids_defended: u32,
+    /// 所做开、定按当时信息的期望收益之和
+    pub expected_call_value: f64,
+    /// 所做开、定的实际收益之和，与期望之差即这些决策上的运气
+    pub actual_call_value: f64,
+    /// 骰运：每局手中最多点数的个数比随机一手的期望多几个，取各局平均
+    pub dice_luck: Option<f64>,
+}
+
+impl MatchReport {
+    /// 由已结算的各局生成复盘
+    pub fn new(state: &GameState) -> Self {
+        let winner = match state.phase {
+            GamePhase::GameOver { winner } => winner,
+            _ => None,
+        };
+        let mut players: Vec<PlayerReport> = state
+            .seats
+            .iter()
+            .enumerate()
+            .map(|(seat, s)| PlayerReport {
+                seat,
+                name: s.name.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let mut probability_sums = vec![0.0; players.len()];
+        let mut luck_sums = vec![(0.0, 0u32); players.len()];
+        let mut expected_best: HashMap<(u32, bool), f64> = HashMap::new();
+        let mut riskiest_bid: Option<RiskyBid> = None;
+
+        for result in state.rounds.iter().map(|r| &r.result) {
+            for review in &result.bids {
+                let player = &mut players[review.seat];
+                player.bids += 1;
+                player.bluffs += u32::from(review.bluff);
+                probability_sums[review.seat] += review.probability;
+                if riskiest_bid
+                    .as_ref()
+                    .is_none_or(|r| review.probability < r.review.probability)
+                {
+                    riskiest_bid = Some(RiskyBid {
+                        round: result.round,
+                        review: review.clone(),
+                    });
+                }
+            }
+
+            let caller = result.call.seat;
+            let caller_won = result.winner == caller;
+            let payoff = result.stake as f64;
+            let player = &mut players[caller];
+            player.calls += 1;
+            player.correct_calls += u32::from(caller_won);
+            player.expected_call_value += result.call.expected_value;
+            player.actual_call_value += if caller_won { payoff } else { -payoff };
+
+            let bidder = if caller_won {
+                result.loser
+            } else {
+                result.winner
+            };
+            players[bidder].bids_called += 1;
+            players[bidder].bids_defended += u32::from(!caller_won);
+
+            let wilds_active = state.config.wild_ones && result.palifico.is_none();
+            for (seat, dice) in result.dice.iter().enumerate() {
+                if dice.is_empty() {
+                    continue;
+                }
+                let count = dice.len() as u32;
+                let expected = *expected_best
+                    .entry((count, wilds_active))
+                    .or_insert_with(|| expected_best_count(&state.config, count, wilds_active));
+                let best = best_count(dice, state.config.die_sides, wilds_active);
+                luck_sums[seat].0 += best as f64 - expected;
+                luck_sums[seat].1 += 1;
+            }
+        }
+
+        for (seat, player) in players.iter_mut().enumerate() {
+            player.bluff_rate = ratio(player.bluffs as f64, player.bids);
+            player.average_bid_probability = ratio(probability_sums[seat], player.bids);
+            player.call_accuracy = ratio(player.correct_calls as f64, player.calls);
+            player.dice_luck = ratio(luck_sums[seat].0, luck_sums[seat].1);
+        }
+
+        MatchReport {
+            winner,
+            rounds: state.rounds.clone(),
+            players,
+            riskiest_bid,
+        }
+    }
+}
+
+fn ratio(total: f64, count: u32) -> Option<f64> {
+    (count > 0).then(|| total / count as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::dice::ScriptedDice;
+    use crate::game::engine::GameEngine;
+
+    const HUMAN: usize = 0;
+    const AI: usize = 1;
+
+    /// 一局定胜负的对局：人类叫到不可能的数量，AI 必然开
+    fn finished_match() -> GameEngine {
+        let mut engine = GameEngine::new();
+        let config = GameConfig {
+            max_rounds: 1,
+            ..GameConfig::default()
+        };
+        let dice = ScriptedDice::new([2, 2, 3, 3, 6, 6, 6, 6, 6, 5]);
+        engine.start_game_with_dice(config, Box::new(dice)).unwrap();
+        engine.player_bid(HUMAN, 10, 6, false).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_report_scores_calls_bids_and_luck() {
+        let engine = finished_match();
+        let report = engine.match_report().unwrap();
+        assert_eq!(report.winner, Some(AI));
+        assert_eq!(report.rounds.len(), 1);
+
+        let human = &report.players[HUMAN];
+        assert_eq!((human.bids, human.bluffs), (1, 0));
+        assert_eq!(human.bluff_rate, Some(0.0));
+        assert_eq!(human.average_bid_probability, Some(0.0));
+        assert_eq!((human.bids_called, human.bids_defended), (1, 0));
+        assert_eq!(human.call_accuracy, None);
+
+        let ai = &report.players[AI];
+        assert_eq!((ai.calls, ai.correct_calls), (1, 1));
+        assert_eq!(ai.call_accuracy, Some(1.0));
+        assert_eq!(ai.expected_call_value, 1.0);
+        assert_eq!(ai.actual_call_value, 1.0);
+
+        // AI 四个 6 远好于平均，人类只有一对
+        let expected = expected_best_count(&GameConfig::default(), 5, false);
+        assert!((ai.dice_luck.unwrap() - (4.0 - expected)).abs() < 1e-12);
+        assert!((human.dice_luck.unwrap() - (2.0 - expected)).abs() < 1e-12);
+
+        let riskiest = report.riskiest_bid.unwrap();
+        assert_eq!((riskiest.round, riskiest.review.seat), (1, HUMAN));
+    }
+
+    #[test]
+    fn test_report_only_after_game_over() {
+        let mut engine = GameEngine::new();
+        engine
+            .start_game_with_dice(GameConfig::default(), Box::new(ScriptedDice::new([2, 3])))
+            .unwrap();
+        assert!(engine.match_report().is_err());
+    }
+}
@@ -0,0 +1,10 @@
+use crate::game::types::{Action, PlayerObservation};
+
+/// AI 座位的决策策略。
+///
+/// 策略只能看到 `PlayerObservation`：自己的骰子与公开场面，看不到任何对手的骰子。
+/// 引擎为每个 AI 座位持有一个策略，可通过 `GameEngine::set_strategy` 随时替换
+pub trait Strategy: Send {
+    /// 轮到该座位时给出行动：叫数、开或定
+    fn decide(&mut self, observation: &PlayerObservation) -> Action;
+}
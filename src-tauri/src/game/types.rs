@@ -1,143 +1,568 @@
-use rand::Rng;
+use crate::game::dice::{DiceSource, SeededDice};
+use crate::game::odds::{challenge_value, spot_on_value, Odds, OddsQuery};
+use crate::game::profile::PlayerProfile;
 use serde::{Deserialize, Serialize};
 
-pub const MAX_DICE_PER_PLAYER: u32 = 5;
-pub const MAX_ROUNDS: u32 = 5;
+/// 默认规则：每人 5 颗六面骰，固定 5 局
+pub const DEFAULT_DICE_PER_PLAYER: u32 = 5;
+pub const DEFAULT_DIE_SIDES: u32 = 6;
+pub const DEFAULT_ROUNDS: u32 = 5;
+/// 可配置的每人骰子数与骰子面数上下限
+pub const MAX_DICE_PER_PLAYER: u32 = 10;
+pub const MIN_DIE_SIDES: u32 = 4;
+pub const MAX_DIE_SIDES: u32 = 12;
+/// 一桌允许的玩家数范围
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 8;
+/// 「定」（恰好命中）成功时赢得的胜场数（失骰赛制下为对手失去的骰子数）
+pub const SPOT_ON_STAKE: u32 = 2;
+/// 「1 点为百搭」规则下的百搭点数
+pub const WILD_FACE: u32 = 1;
+/// 顺子重掷规则要求的每人骰子数下限：骰子更少时点数各不相同很常见，算不上特殊牌型
+pub const MIN_STRAIGHT_DICE: u32 = 5;
+
+/// 赛制
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum MatchFormat {
+    /// 固定局数，按胜场定胜负，骰子数不变
+    #[default]
+    FixedRounds,
+    /// 经典 Perudo：每局输家失去骰子，最后仍有骰子的一方获胜
+    DiceLoss,
+}
+
+/// 固定局数赛制打满后胜场相同时的判定方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum TieBreak {
+    /// 判为平局
+    #[default]
+    Draw,
+    /// 加赛，直到胜场最多者唯一
+    SuddenDeath,
+    /// 并列者中剩余骰子最多者获胜，仍相同则为平局
+    MostDice,
+    /// 座位号小者优先
+    SeatOrder,
+    /// 最后一局的胜者优先（若其在并列者中），否则按座位号
+    LastRoundWinner,
+}
+
+/// 每局先叫座位的轮换规则
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum OpenerRule {
+    /// 每局都由 `first_opener` 先叫
+    #[default]
+    Fixed,
+    /// 上一局输家先叫
+    LoserOpens,
+    /// 上一局胜者先叫
+    WinnerOpens,
+    /// 按座位顺序轮流先叫
+    Alternate,
+    /// 每局（含首局）随机选一个座位先叫
+    Random,
+}
+
+/// AI 难度
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Expert,
+    /// 蒙特卡洛搜索 AI
+    Master,
+}
+
+/// AI 的性格：一组覆盖难度默认值的门槛、诈叫倾向与加注大小偏好，并附带台词
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Personality {
+    /// 稳健：早开、只叫有把握的数，从不诈叫
+    Cautious,
+    /// 莽撞：迟开、大幅跳叫、常诈叫
+    Reckless,
+    /// 数学家：严格按概率，加注前权衡开的胜率
+    Mathematician,
+    /// 诈唬者：门槛适中，但一半的加注是诈叫
+    Bluffer,
+}
+
+/// 座位上的玩家类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum PlayerKind {
+    Human,
+    AI,
+}
+
+/// 座位配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlayerConfig {
+    pub name: String,
+    pub kind: PlayerKind,
+    /// AI 的性格：加注时诈叫（叫自己手中没有的点数）的概率，为空时按难度
+    #[serde(default)]
+    pub bluff_rate: Option<f64>,
+    /// AI 的性格，为空时按难度；`bluff_rate` 仍可单独覆盖诈叫倾向
+    #[serde(default)]
+    pub personality: Option<Personality>,
+}
+
+impl PlayerConfig {
+    pub fn human(name: &str) -> Self {
+        PlayerConfig {
+            name: name.to_string(),
+            kind: PlayerKind::Human,
+            bluff_rate: None,
+            personality: None,
+        }
+    }
+
+    pub fn ai(name: &str) -> Self {
+        PlayerConfig {
+            name: name.to_string(),
+            kind: PlayerKind::AI,
+            bluff_rate: None,
+            personality: None,
+        }
+    }
+}
+
+/// 对局规则配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct GameConfig {
+    /// 1 点为百搭：叫其他点数时 1 点也算作该点数
+    pub wild_ones: bool,
+    pub format: MatchFormat,
+    /// 失骰赛制下，玩家首次只剩 1 颗骰子时下一局为「独骰局」（palifico）
+    pub palifico: bool,
+    /// 按座位顺序排列的玩家，叫数按此顺序轮转
+    pub players: Vec<PlayerConfig>,
+    pub dice_per_player: u32,
+    pub die_sides: u32,
+    /// 固定局数赛制的总局数
+    pub max_rounds: u32,
+    /// 整手骰子点数各不相同（顺子）时自动重掷；要求每人至少 `MIN_STRAIGHT_DICE` 颗骰子
+    pub reroll_straights: bool,
+    /// 首局先叫的座位号；该座位出局后由其下家先叫
+    pub first_opener: usize,
+    pub opener_rule: OpenerRule,
+    pub tie_break: TieBreak,
+    /// 骰子种子；为空时随机生成。相同种子与相同操作可完整重放一场。
+    /// 指定种子时 Master 的搜索不设用时上限，决策才能逐步重放
+    pub seed: Option<u64>,
+    /// 所有 AI 座位的难度
+    pub difficulty: Difficulty,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            wild_ones: false,
+            format: MatchFormat::default(),
+            palifico: false,
+            players: vec![PlayerConfig::human("You"), PlayerConfig::ai("AI")],
+            dice_per_player: DEFAULT_DICE_PER_PLAYER,
+            die_sides: DEFAULT_DIE_SIDES,
+            max_rounds: DEFAULT_ROUNDS,
+            reroll_straights: true,
+            first_opener: 0,
+            opener_rule: OpenerRule::default(),
+            tie_break: TieBreak::default(),
+            seed: None,
+            difficulty: Difficulty::default(),
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&self.players.len()) {
+            return Err(format!(
+                "A table needs between {} and {} players",
+                MIN_PLAYERS, MAX_PLAYERS
+            ));
+        }
+        self.validate_rules()?;
+        if self.max_rounds < 1 {
+            return Err("A match needs at least one round".to_string());
+        }
+        if self.first_opener >= self.players.len() {
+            return Err("Opening seat is not at the table".to_string());
+        }
+        if self
+            .players
+            .iter()
+            .filter_map(|p| p.bluff_rate)
+            .any(|rate| !(0.0..=1.0).contains(&rate))
+        {
+            return Err("Bluff rate must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// 只校验决定掷骰与叫数的规则（骰子数、面数、顺子重掷），不涉及座位与局数；
+    /// 不依赖具体一桌的概率查询只需这部分
+    pub fn validate_rules(&self) -> Result<(), String> {
+        if !(1..=MAX_DICE_PER_PLAYER).contains(&self.dice_per_player) {
+            return Err(format!(
+                "Dice per player must be between 1 and {}",
+                MAX_DICE_PER_PLAYER
+            ));
+        }
+        if !(MIN_DIE_SIDES..=MAX_DIE_SIDES).contains(&self.die_sides) {
+            return Err(format!(
+                "Die sides must be between {} and {}",
+                MIN_DIE_SIDES, MAX_DIE_SIDES
+            ));
+        }
+        if self.reroll_straights && self.dice_per_player < MIN_STRAIGHT_DICE {
+            return Err(format!(
+                "Rerolling straights needs at least {} dice per player",
+                MIN_STRAIGHT_DICE
+            ));
+        }
+        Ok(())
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Bid {
     pub count: u32,
     pub face: u32,
+    /// 斋：该叫数中 1 点不作百搭
+    #[serde(default)]
+    pub pure: bool,
 }
 
 impl Bid {
+    pub fn new(count: u32, face: u32) -> Self {
+        Bid {
+            count,
+            face,
+            pure: false,
+        }
+    }
+
+    pub fn new_pure(count: u32, face: u32) -> Self {
+        Bid {
+            count,
+            face,
+            pure: true,
+        }
+    }
+
+    /// 叫数排序键：斋叫的数量按两倍计；等量时斋高于飞，同模式再比点数。
+    /// 因此飞转斋可以减半数量（向上取整），斋转飞至少要翻倍加一。
+    fn order_key(&self) -> (u32, bool, u32) {
+        let weight = if self.pure {
+            self.count * 2
+        } else {
+            self.count
+        };
+        (weight, self.pure, self.face)
+    }
+
+    /// 是否为合法加注；点数上限取决于骰子面数，由 `GameState::validate_bid` 检查
     pub fn is_valid_raise(&self, new_bid: &Bid) -> bool {
-        if new_bid.face < 1 || new_bid.face > 6 || new_bid.count < 1 {
+        if new_bid.face < 1 || new_bid.count < 1 {
             return false;
         }
-        new_bid.count > self.count || (new_bid.count == self.count && new_bid.face > self.face)
+        new_bid.order_key() > self.order_key()
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
-pub enum Player {
-    Human,
-    AI,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Action {
     Bid(Bid),
     Challenge,
+    /// 定：声称当前叫数恰好等于实际数量
+    SpotOn,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RoundResult {
     pub round: u32,
-    pub winner: Player,
-    pub loser: Player,
-    pub human_dice: Vec<u32>,
-    pub ai_dice: Vec<u32>,
+    /// 胜者与输家的座位号
+    pub winner: usize,
+    pub loser: usize,
+    /// 按座位号排列的全部骰子，结算时公开
+    pub dice: Vec<Vec<u32>>,
+    /// 本局每次叫数的复盘，可看出谁在诈叫
+    pub bids: Vec<BidReview>,
+    /// 结束本局的开或定的复盘
+    pub call: CallReview,
     pub last_bid: Bid,
     pub actual_count: u32,
+    /// actual_count 中由百搭 1 点贡献的数量
+    pub wild_count: u32,
+    /// 本局是否以「定」结算
+    pub spot_on: bool,
+    /// 独骰局的触发座位，此局百搭失效
+    pub palifico: Option<usize>,
+    /// 胜者赢得的胜场数；失骰赛制下为输家失去的骰子数
+    pub stake: u32,
+    /// 本场的骰子种子，用于重放
+    pub seed: Option<u64>,
+}
+
+/// 一局结束后留存的完整记录，供记分板、复盘与重放使用
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompletedRound {
+    /// 本局先叫的座位
+    pub opener: usize,
+    /// 本局全部行动，最后一个是开或定
+    pub bid_history: Vec<(usize, Action)>,
+    pub taunts: Vec<Taunt>,
+    /// 结算结果，含公开的全部骰子与每次叫数的复盘
+    pub result: RoundResult,
+}
+
+/// 结算后对本局一次叫数的复盘
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BidReview {
+    pub seat: usize,
+    pub bid: Bid,
+    /// 叫数者自己手中计入该叫数的个数
+    pub held: u32,
+    /// 诈叫：叫的是自己手中一个都没有的点数
+    pub bluff: bool,
+    /// 只凭叫数者当时的信息（自己的骰子与各家骰子数），该叫数成立的概率；
+    /// 越低说明这次叫数越冒险
+    pub probability: f64,
+}
+
+/// 结算后对开或定的复盘，按叫的人当时的信息计算，
+/// 用来说明这次叫得好不好，而不只是结果如何
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CallReview {
+    pub seat: usize,
+    /// 在他看来被开叫数成立、恰好成立的概率
+    pub probability: f64,
+    pub exact_probability: f64,
+    /// 这次开或定的期望收益（胜场或骰子数）
+    pub expected_value: f64,
+    /// 改为另一种叫法（开换成定、定换成开）的期望收益
+    pub alternative_value: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum GamePhase {
-    PlayerTurn,
-    AITurn,
+    /// 轮到该座位行动
+    Turn(usize),
     RoundOver(RoundResult),
-    GameOver { winner: Player },
+    /// `winner` 为 `None` 表示平局
+    GameOver {
+        winner: Option<usize>,
+    },
+}
+
+/// 座位的公开信息，不含骰子
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeatView {
+    pub name: String,
+    pub kind: PlayerKind,
+    pub personality: Option<Personality>,
+    pub dice_count: u32,
+    pub wins: u32,
+}
+
+/// 一个加注及其在提示者视角下的成立概率
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RaiseOdds {
+    pub bid: Bid,
+    pub probability: f64,
+}
+
+/// 给人类玩家的提示：用 AI 的概率估计，从该玩家自己的视角计算
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Hint {
+    /// 当前叫数成立（至少这么多）的概率，尚无叫数时为空
+    pub bid_probability: Option<f64>,
+    /// 当前叫数恰好成立的概率，即定的胜率
+    pub exact_probability: Option<f64>,
+    /// 成立概率最高的几个合法加注，从高到低
+    pub raises: Vec<RaiseOdds>,
+    pub recommended: Action,
+}
+
+/// AI 行动时按性格说的一句台词
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Taunt {
+    pub seat: usize,
+    /// 对应行动在本局 `bid_history` 中的下标
+    pub action: usize,
+    pub line: String,
 }
 
+/// 某个座位视角下的对局状态：只包含该座位自己的骰子
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameView {
+    pub seat: usize,
     pub phase: GamePhase,
-    pub human_dice: Vec<u32>,
-    pub ai_dice_count: u32,
-    pub human_dice_count: u32,
-    pub bid_history: Vec<(Player, Action)>,
+    pub dice: Vec<u32>,
+    pub seats: Vec<SeatView>,
+    pub bid_history: Vec<(usize, Action)>,
+    pub taunts: Vec<Taunt>,
     pub current_bid: Option<Bid>,
+    pub current_turn: usize,
+    /// 本局先叫的座位
+    pub opener: usize,
     pub current_round: u32,
     pub max_rounds: u32,
-    pub human_wins: u32,
-    pub ai_wins: u32,
     pub last_round_result: Option<RoundResult>,
+    /// 本场已结算的各局，骰子均已公开
+    pub rounds: Vec<CompletedRound>,
+    pub config: GameConfig,
+    pub palifico: Option<usize>,
+    pub locked_face: Option<u32>,
+    /// 本场的骰子种子，整场结束后才公开
+    pub seed: Option<u64>,
+    pub difficulty: Difficulty,
+    /// 人类玩家档案读写的问题，例如损坏的档案已备份、写档失败
+    pub profile_warning: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Seat {
+    pub name: String,
+    pub kind: PlayerKind,
+    pub personality: Option<Personality>,
+    pub dice: Vec<u32>,
+    pub dice_count: u32,
+    pub wins: u32,
+}
+
+impl Seat {
+    fn from_config(player: &PlayerConfig, dice_count: u32) -> Self {
+        Seat {
+            name: player.name.clone(),
+            kind: player.kind,
+            personality: player.personality,
+            dice: Vec::new(),
+            dice_count,
+            wins: 0,
+        }
+    }
+
+    /// 失骰赛制下骰子输光即出局
+    pub fn is_active(&self) -> bool {
+        self.dice_count > 0
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct GameState {
-    pub human_dice: Vec<u32>,
-    pub ai_dice: Vec<u32>,
-    pub human_dice_count: u32,
-    pub ai_dice_count: u32,
+    pub seats: Vec<Seat>,
     pub phase: GamePhase,
-    pub bid_history: Vec<(Player, Action)>,
+    pub bid_history: Vec<(usize, Action)>,
+    /// 本局有性格的 AI 的台词
+    pub taunts: Vec<Taunt>,
     pub current_bid: Option<Bid>,
-    pub current_turn: Player,
+    pub current_turn: usize,
+    /// 本局先叫的座位
+    pub opener: usize,
     pub current_round: u32,
     pub max_rounds: u32,
-    pub human_wins: u32,
-    pub ai_wins: u32,
     pub last_round_result: Option<RoundResult>,
+    /// 本场已结算的各局，按局序；不随新一局掷骰清空
+    pub rounds: Vec<CompletedRound>,
+    pub config: GameConfig,
+    /// 独骰局中只剩 1 颗骰子的座位：由其先叫，点数锁定，百搭失效
+    pub palifico: Option<usize>,
+    /// 骰子来源的种子，脚本骰子为 `None`
+    pub seed: Option<u64>,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GameState {
     pub fn new() -> Self {
+        Self::with_config(GameConfig::default())
+    }
+
+    /// 按配置建桌；骰子由 `config.seed`（为空则随机种子）决定
+    pub fn with_config(config: GameConfig) -> Self {
+        let mut dice = match config.seed {
+            Some(seed) => SeededDice::new(seed),
+            None => SeededDice::from_entropy(),
+        };
+        Self::with_dice(config, &mut dice)
+    }
+
+    /// 按配置建桌，并用给定的骰子来源掷首局骰子
+    pub fn with_dice(config: GameConfig, dice: &mut dyn DiceSource) -> Self {
         let mut state = GameState {
-            human_dice: Vec::new(),
-            ai_dice: Vec::new(),
-            human_dice_count: MAX_DICE_PER_PLAYER,
-            ai_dice_count: MAX_DICE_PER_PLAYER,
-            phase: GamePhase::PlayerTurn,
+            seats: config
+                .players
+                .iter()
+                .map(|p| Seat::from_config(p, config.dice_per_player))
+                .collect(),
+            phase: GamePhase::Turn(0),
             bid_history: Vec::new(),
+            taunts: Vec::new(),
             current_bid: None,
-            current_turn: Player::Human,
+            current_turn: 0,
+            opener: 0,
             current_round: 1,
-            max_rounds: MAX_ROUNDS,
-            human_wins: 0,
-            ai_wins: 0,
+            max_rounds: config.max_rounds,
             last_round_result: None,
+            rounds: Vec::new(),
+            config,
+            palifico: None,
+            seed: dice.seed(),
         };
-        state.roll_all_dice();
+        state.roll_all_dice(dice);
         state
     }
 
-    pub fn roll_all_dice(&mut self) {
-        let mut rng = rand::thread_rng();
-        self.human_dice = Self::roll_dice_with_auto_reroll(&mut rng, self.human_dice_count);
-        self.ai_dice = Self::roll_dice_with_auto_reroll(&mut rng, self.ai_dice_count);
+    pub fn roll_all_dice(&mut self, dice: &mut dyn DiceSource) {
+        for seat in &mut self.seats {
+            seat.dice = Self::roll_dice_with_auto_reroll(&self.config, dice, seat.dice_count);
+        }
         self.bid_history.clear();
+        self.taunts.clear();
         self.current_bid = None;
         self.last_round_result = None;
     }
 
-    fn roll_dice_with_auto_reroll(rng: &mut impl Rng, count: u32) -> Vec<u32> {
-        Self::roll_dice_with_auto_reroll_using(count, || {
-            (0..count).map(|_| rng.gen_range(1..=6)).collect()
+    fn roll_dice_with_auto_reroll(
+        config: &GameConfig,
+        dice: &mut dyn DiceSource,
+        count: u32,
+    ) -> Vec<u32> {
+        Self::roll_dice_with_auto_reroll_using(config, || {
+            (0..count).map(|_| dice.roll(config.die_sides)).collect()
         })
     }
 
-    fn roll_dice_with_auto_reroll_using<F>(count: u32, mut roll_once: F) -> Vec<u32>
+    fn roll_dice_with_auto_reroll_using<F>(config: &GameConfig, mut roll_once: F) -> Vec<u32>
     where
         F: FnMut() -> Vec<u32>,
     {
         loop {
             let rolled = roll_once();
-            if count != MAX_DICE_PER_PLAYER || !Self::has_five_distinct(&rolled) {
+            if !config.reroll_straights || !Self::is_straight(config, &rolled) {
                 return rolled;
             }
         }
     }
 
-    fn has_five_distinct(dice: &[u32]) -> bool {
-        if dice.len() != MAX_DICE_PER_PLAYER as usize {
+    /// 满手（至少 `MIN_STRAIGHT_DICE` 颗）骰子点数各不相同即为顺子；掉过骰子的手不算
+    fn is_straight(config: &GameConfig, dice: &[u32]) -> bool {
+        if dice.len() < MIN_STRAIGHT_DICE as usize || dice.len() != config.dice_per_player as usize
+        {
             return false;
         }
 
-        let mut seen = [false; 7];
+        let mut seen = vec![false; config.die_sides as usize + 1];
         for &value in dice {
-            if !(1..=6).contains(&value) {
+            if !(1..=config.die_sides).contains(&value) {
                 return false;
             }
 
@@ -151,26 +576,399 @@ impl GameState {
         true
     }
 
-    pub fn to_view(&self) -> GameView {
+    /// 生成 `seat` 视角的状态，其他座位的骰子只公开数量
+    /// 种子能重算全部骰子，因此只在整场结束后放进视角，`config` 中的种子总是去掉
+    pub fn view_for(&self, seat: usize) -> GameView {
+        let seed = match self.phase {
+            GamePhase::GameOver { .. } => self.seed,
+            _ => None,
+        };
+        let redact = |result: &RoundResult| RoundResult {
+            seed,
+            ..result.clone()
+        };
+        let phase = match &self.phase {
+            GamePhase::RoundOver(result) => GamePhase::RoundOver(redact(result)),
+            phase => phase.clone(),
+        };
         GameView {
-            phase: self.phase.clone(),
-            human_dice: self.human_dice.clone(),
-            ai_dice_count: self.ai_dice_count,
-            human_dice_count: self.human_dice_count,
+            seat,
+            phase,
+            dice: self.seats[seat].dice.clone(),
+            seats: self.seat_views(),
             bid_history: self.bid_history.clone(),
+            taunts: self.taunts.clone(),
             current_bid: self.current_bid.clone(),
+            current_turn: self.current_turn,
+            opener: self.opener,
             current_round: self.current_round,
             max_rounds: self.max_rounds,
-            human_wins: self.human_wins,
-            ai_wins: self.ai_wins,
-            last_round_result: self.last_round_result.clone(),
+            last_round_result: self.last_round_result.as_ref().map(redact),
+            rounds: self
+                .rounds
+                .iter()
+                .map(|round| CompletedRound {
+                    result: redact(&round.result),
+                    ..round.clone()
+                })
+                .collect(),
+            config: GameConfig {
+                seed: None,
+                ..self.config.clone()
+            },
+            palifico: self.palifico,
+            locked_face: self.locked_face(),
+            seed,
+            difficulty: self.config.difficulty,
+            profile_warning: None,
+        }
+    }
+
+    /// 仍有骰子的座位
+    pub fn active_seats(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.seats.len()).filter(|&i| self.seats[i].is_active())
+    }
+
+    /// `seat` 之后按座位顺序的下一个仍有骰子的座位
+    pub fn next_seat(&self, seat: usize) -> usize {
+        let n = self.seats.len();
+        (1..=n)
+            .map(|offset| (seat + offset) % n)
+            .find(|&i| self.seats[i].is_active())
+            .unwrap_or(seat)
+    }
+
+    /// 当前叫数的叫数者
+    pub fn last_bidder(&self) -> Option<usize> {
+        self.bid_history
+            .iter()
+            .rev()
+            .find_map(|(seat, action)| matches!(action, Action::Bid(_)).then_some(*seat))
+    }
+
+    /// 场上骰子总数
+    pub fn total_dice(&self) -> u32 {
+        self.seats.iter().map(|s| s.dice_count).sum()
+    }
+
+    /// 除 `seat` 外其他座位的骰子总数
+    pub fn opponent_dice_count(&self, seat: usize) -> u32 {
+        self.total_dice() - self.seats[seat].dice_count
+    }
+
+    /// 本局百搭是否生效：独骰局中百搭失效
+    pub fn wilds_active(&self) -> bool {
+        self.rules().wilds_active()
+    }
+
+    /// 独骰局中首个叫数锁定的点数
+    pub fn locked_face(&self) -> Option<u32> {
+        self.rules().locked_face()
+    }
+
+    /// 骰面 `die` 是否计入叫数点数 `face`；斋叫（`pure`）时 1 点不再百搭
+    pub fn counts_as(&self, die: u32, face: u32, pure: bool) -> bool {
+        self.rules().counts_as(die, face, pure)
+    }
+
+    /// 统计场上计入 `face` 的骰子数，百搭规则下非斋叫包含 1 点
+    pub fn count_face(&self, face: u32, pure: bool) -> u32 {
+        self.seats
+            .iter()
+            .map(|s| self.count_in(&s.dice, face, pure))
+            .sum()
+    }
+
+    /// 统计 `count_face(face, pure)` 中由百搭 1 点贡献的数量
+    pub fn count_wilds(&self, face: u32, pure: bool) -> u32 {
+        if !self.wilds_active() || pure || face == WILD_FACE {
+            return 0;
+        }
+        self.seats
+            .iter()
+            .flat_map(|s| s.dice.iter())
+            .filter(|&&d| d == WILD_FACE)
+            .count() as u32
+    }
+
+    /// 统计一组骰子中计入 `face` 的数量
+    pub fn count_in(&self, dice: &[u32], face: u32, pure: bool) -> u32 {
+        self.rules().count_in(dice, face, pure)
+    }
+
+    /// 按当前规则规范叫数：百搭规则下叫 1 点总是斋
+    pub fn normalize_bid(&self, bid: Bid) -> Result<Bid, String> {
+        self.rules().normalize_bid(bid)
+    }
+
+    /// 按本局上下文校验叫数，返回规范后的叫数：数量在 1 到场上骰子总数之间，
+    /// 独骰局中点数锁定，其余情况按 `Bid::is_valid_raise` 的顺序加注
+    pub fn validate_bid(&self, bid: Bid) -> Result<Bid, String> {
+        self.rules().validate_bid(bid)
+    }
+
+    /// 按已公开的骰子复盘本局全部叫数
+    pub fn review_bids(&self) -> Vec<BidReview> {
+        self.bid_history
+            .iter()
+            .filter_map(|(seat, action)| match action {
+                Action::Bid(bid) => {
+                    let held = self.count_in(&self.seats[*seat].dice, bid.face, bid.pure);
+                    Some(BidReview {
+                        seat: *seat,
+                        bid: bid.clone(),
+                        held,
+                        bluff: held == 0,
+                        probability: self.odds_for(*seat, bid).at_least,
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 复盘 `caller` 对当前叫数的开（`spot_on` 为定）
+    pub fn review_call(&self, caller: usize, spot_on: bool) -> CallReview {
+        let bid = self.current_bid.as_ref().expect("a call requires a bid");
+        let odds = self.odds_for(caller, bid);
+        let challenge = challenge_value(odds.at_least);
+        let spot = spot_on_value(odds.exactly);
+        let (expected_value, alternative_value) = if spot_on {
+            (spot, challenge)
+        } else {
+            (challenge, spot)
+        };
+        CallReview {
+            seat: caller,
+            probability: odds.at_least,
+            exact_probability: odds.exactly,
+            expected_value,
+            alternative_value,
+        }
+    }
+
+    /// `seat` 只凭自己的信息看 `bid` 的概率。局中出现的叫数都已校验过
+    fn odds_for(&self, seat: usize, bid: &Bid) -> Odds {
+        OddsQuery::for_seat(self, seat, bid)
+            .odds()
+            .expect("bids in play are valid")
+    }
+
+    /// 生成 `seat` 决策时可见的信息，供 AI 策略使用
+    pub fn observation_for(&self, seat: usize) -> PlayerObservation {
+        PlayerObservation {
+            seat,
+            dice: self.seats[seat].dice.clone(),
+            seats: self.seat_views(),
+            bid_history: self.bid_history.clone(),
+            current_bid: self.current_bid.clone(),
+            current_round: self.current_round,
+            // 种子能重算全部骰子，不交给策略
+            config: GameConfig {
+                seed: None,
+                ..self.config.clone()
+            },
+            palifico: self.palifico,
+            profiles: vec![None; self.seats.len()],
+        }
+    }
+
+    fn seat_views(&self) -> Vec<SeatView> {
+        self.seats
+            .iter()
+            .map(|s| SeatView {
+                name: s.name.clone(),
+                kind: s.kind,
+                personality: s.personality,
+                dice_count: s.dice_count,
+                wins: s.wins,
+            })
+            .collect()
+    }
+
+    fn rules(&self) -> Rules<'_> {
+        Rules {
+            config: &self.config,
+            palifico: self.palifico,
+            bid_history: &self.bid_history,
+            current_bid: self.current_bid.as_ref(),
+            total_dice: self.total_dice(),
+        }
+    }
+}
+
+/// 某座位决策时可见的信息：自己的骰子与公开的场面。
+///
+/// 这是 AI 决策的唯一输入。结构中没有存放对手骰子的位置，也不引用 `GameState`，
+/// 因此策略无论如何实现都拿不到对手的骰子
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerObservation {
+    pub seat: usize,
+    pub dice: Vec<u32>,
+    pub seats: Vec<SeatView>,
+    pub bid_history: Vec<(usize, Action)>,
+    pub current_bid: Option<Bid>,
+    pub current_round: u32,
+    pub config: GameConfig,
+    pub palifico: Option<usize>,
+    /// 按座位排列的人类玩家历史档案，供 AI 适应其习惯；没有档案的座位为 `None`
+    pub profiles: Vec<Option<PlayerProfile>>,
+}
+
+impl PlayerObservation {
+    /// 按配置开局时 `seat` 的视角：各座位满骰、尚无叫数，自己的骰子为 `dice`
+    pub fn new(config: GameConfig, seat: usize, dice: Vec<u32>) -> Self {
+        PlayerObservation {
+            seat,
+            dice,
+            seats: config
+                .players
+                .iter()
+                .map(|p| SeatView {
+                    name: p.name.clone(),
+                    kind: p.kind,
+                    personality: p.personality,
+                    dice_count: config.dice_per_player,
+                    wins: 0,
+                })
+                .collect(),
+            bid_history: Vec::new(),
+            current_bid: None,
+            current_round: 1,
+            profiles: vec![None; config.players.len()],
+            config,
+            palifico: None,
+        }
+    }
+
+    /// 场上骰子总数
+    pub fn total_dice(&self) -> u32 {
+        self.seats.iter().map(|s| s.dice_count).sum()
+    }
+
+    /// 除自己外仍有骰子的座位
+    pub fn opponents(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.seats.len()).filter(|&i| i != self.seat && self.seats[i].dice_count > 0)
+    }
+
+    /// 自己叫数后接着行动的座位
+    pub fn next_opponent(&self) -> Option<usize> {
+        let n = self.seats.len();
+        (1..n)
+            .map(|offset| (self.seat + offset) % n)
+            .find(|&i| self.seats[i].dice_count > 0)
+    }
+
+    pub fn profile(&self, seat: usize) -> Option<&PlayerProfile> {
+        self.profiles.get(seat)?.as_ref()
+    }
+
+    /// 本局百搭是否生效：独骰局中百搭失效
+    pub fn wilds_active(&self) -> bool {
+        self.rules().wilds_active()
+    }
+
+    /// 独骰局中首个叫数锁定的点数
+    pub fn locked_face(&self) -> Option<u32> {
+        self.rules().locked_face()
+    }
+
+    /// 统计一组骰子中计入 `face` 的数量
+    pub fn count_in(&self, dice: &[u32], face: u32, pure: bool) -> u32 {
+        self.rules().count_in(dice, face, pure)
+    }
+
+    /// 自己手中计入 `face` 的数量
+    pub fn my_count(&self, face: u32, pure: bool) -> u32 {
+        self.count_in(&self.dice, face, pure)
+    }
+
+    /// 与 `GameState::validate_bid` 相同的校验，便于策略只提出合法叫数
+    pub fn validate_bid(&self, bid: Bid) -> Result<Bid, String> {
+        self.rules().validate_bid(bid)
+    }
+
+    fn rules(&self) -> Rules<'_> {
+        Rules {
+            config: &self.config,
+            palifico: self.palifico,
+            bid_history: &self.bid_history,
+            current_bid: self.current_bid.as_ref(),
+            total_dice: self.total_dice(),
         }
     }
+}
+
+/// 判定叫数只依赖公开场面，由 `GameState` 与 `PlayerObservation` 共用
+struct Rules<'a> {
+    config: &'a GameConfig,
+    palifico: Option<usize>,
+    bid_history: &'a [(usize, Action)],
+    current_bid: Option<&'a Bid>,
+    /// 场上骰子总数，叫数不能超过它
+    total_dice: u32,
+}
+
+impl Rules<'_> {
+    fn wilds_active(&self) -> bool {
+        self.config.wild_ones && self.palifico.is_none()
+    }
+
+    fn locked_face(&self) -> Option<u32> {
+        self.palifico?;
+        self.bid_history
+            .iter()
+            .find_map(|(_, action)| match action {
+                Action::Bid(bid) => Some(bid.face),
+                _ => None,
+            })
+    }
+
+    fn counts_as(&self, die: u32, face: u32, pure: bool) -> bool {
+        die == face || (self.wilds_active() && !pure && die == WILD_FACE && face != WILD_FACE)
+    }
+
+    fn count_in(&self, dice: &[u32], face: u32, pure: bool) -> u32 {
+        dice.iter()
+            .filter(|&&d| self.counts_as(d, face, pure))
+            .count() as u32
+    }
+
+    fn normalize_bid(&self, bid: Bid) -> Result<Bid, String> {
+        if !self.wilds_active() {
+            if bid.pure {
+                return Err("Pure bids require the wild ones rule".to_string());
+            }
+            return Ok(bid);
+        }
+        Ok(Bid {
+            pure: bid.pure || bid.face == WILD_FACE,
+            ..bid
+        })
+    }
 
-    pub fn count_face(&self, face: u32) -> u32 {
-        let human_count = self.human_dice.iter().filter(|&&d| d == face).count() as u32;
-        let ai_count = self.ai_dice.iter().filter(|&&d| d == face).count() as u32;
-        human_count + ai_count
+    fn validate_bid(&self, bid: Bid) -> Result<Bid, String> {
+        if !(1..=self.total_dice).contains(&bid.count) {
+            return Err(format!("Count must be between 1 and {}", self.total_dice));
+        }
+        if !(1..=self.config.die_sides).contains(&bid.face) {
+            return Err(format!(
+                "Face must be between 1 and {}",
+                self.config.die_sides
+            ));
+        }
+        let bid = self.normalize_bid(bid)?;
+        if let Some(face) = self.locked_face() {
+            if bid.face != face {
+                return Err(format!("Face is locked to {} in a palifico round", face));
+            }
+        }
+        if let Some(current) = self.current_bid {
+            if !current.is_valid_raise(&bid) {
+                return Err("Bid must raise the current bid".to_string());
+            }
+        }
+        Ok(bid)
     }
 }
 
@@ -180,94 +978,467 @@ mod tests {
 
     #[test]
     fn test_bid_valid_raise_higher_count() {
-        let current = Bid { count: 3, face: 4 };
-        let new_bid = Bid { count: 4, face: 2 };
+        let current = Bid::new(3, 4);
+        let new_bid = Bid::new(4, 2);
         assert!(current.is_valid_raise(&new_bid));
     }
 
     #[test]
     fn test_bid_valid_raise_same_count_higher_face() {
-        let current = Bid { count: 3, face: 4 };
-        let new_bid = Bid { count: 3, face: 5 };
+        let current = Bid::new(3, 4);
+        let new_bid = Bid::new(3, 5);
         assert!(current.is_valid_raise(&new_bid));
     }
 
     #[test]
     fn test_bid_invalid_raise_lower() {
-        let current = Bid { count: 3, face: 4 };
-        let new_bid = Bid { count: 2, face: 6 };
+        let current = Bid::new(3, 4);
+        let new_bid = Bid::new(2, 6);
         assert!(!current.is_valid_raise(&new_bid));
     }
 
     #[test]
     fn test_bid_invalid_raise_same() {
-        let current = Bid { count: 3, face: 4 };
-        let new_bid = Bid { count: 3, face: 4 };
+        let current = Bid::new(3, 4);
+        let new_bid = Bid::new(3, 4);
         assert!(!current.is_valid_raise(&new_bid));
     }
 
     #[test]
     fn test_bid_invalid_face_out_of_range() {
-        let current = Bid { count: 1, face: 1 };
-        let new_bid = Bid { count: 2, face: 7 };
-        assert!(!current.is_valid_raise(&new_bid));
+        let state = GameState::new();
+        assert!(state.validate_bid(Bid::new(2, 7)).is_err());
+        assert!(state.validate_bid(Bid::new(2, 0)).is_err());
+    }
+
+    fn table(kinds: &[PlayerKind]) -> GameConfig {
+        GameConfig {
+            players: kinds
+                .iter()
+                .enumerate()
+                .map(|(i, &kind)| PlayerConfig {
+                    name: format!("Seat {}", i),
+                    kind,
+                    bluff_rate: None,
+                    personality: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
     }
 
     #[test]
     fn test_game_state_new() {
         let state = GameState::new();
-        assert_eq!(state.human_dice.len(), 5);
-        assert_eq!(state.ai_dice.len(), 5);
-        assert_eq!(state.human_dice_count, 5);
-        assert_eq!(state.ai_dice_count, 5);
+        assert_eq!(state.seats.len(), 2);
+        assert_eq!(state.seats[0].kind, PlayerKind::Human);
+        assert_eq!(state.seats[1].kind, PlayerKind::AI);
+        for seat in &state.seats {
+            assert_eq!(seat.dice.len(), 5);
+            assert_eq!(seat.dice_count, 5);
+            assert_eq!(seat.wins, 0);
+            assert!(seat.dice.iter().all(|&d| (1..=6).contains(&d)));
+        }
         assert_eq!(state.current_round, 1);
         assert_eq!(state.max_rounds, 5);
-        assert_eq!(state.human_wins, 0);
-        assert_eq!(state.ai_wins, 0);
-        assert!(state.human_dice.iter().all(|&d| (1..=6).contains(&d)));
-        assert!(state.ai_dice.iter().all(|&d| (1..=6).contains(&d)));
+    }
+
+    #[test]
+    fn test_game_config_validate_player_count() {
+        assert!(GameConfig::default().validate().is_ok());
+        assert!(table(&[PlayerKind::Human]).validate().is_err());
+        assert!(table(&[PlayerKind::AI; 8]).validate().is_ok());
+        assert!(table(&[PlayerKind::AI; 9]).validate().is_err());
+    }
+
+    #[test]
+    fn test_game_config_validate_rules() {
+        let invalid = [
+            GameConfig {
+                dice_per_player: 0,
+                ..Default::default()
+            },
+            GameConfig {
+                die_sides: 3,
+                ..Default::default()
+            },
+            GameConfig {
+                max_rounds: 0,
+                ..Default::default()
+            },
+            GameConfig {
+                dice_per_player: 4,
+                ..Default::default()
+            },
+            GameConfig {
+                first_opener: 2,
+                ..Default::default()
+            },
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_count_face_across_seats() {
+        let mut state =
+            GameState::with_config(table(&[PlayerKind::Human, PlayerKind::AI, PlayerKind::AI]));
+        state.seats[0].dice = vec![1, 2, 3, 4, 5];
+        state.seats[1].dice = vec![1, 1, 3, 5, 6];
+        state.seats[2].dice = vec![3, 3, 2, 2, 6];
+        assert_eq!(state.count_face(3, false), 4);
+        assert_eq!(state.total_dice(), 15);
+        assert_eq!(state.opponent_dice_count(2), 10);
+    }
+
+    #[test]
+    fn test_next_seat_skips_eliminated() {
+        let mut state = GameState::with_config(table(&[
+            PlayerKind::Human,
+            PlayerKind::AI,
+            PlayerKind::AI,
+            PlayerKind::AI,
+        ]));
+        assert_eq!(state.next_seat(0), 1);
+        assert_eq!(state.next_seat(3), 0);
+
+        state.seats[1].dice_count = 0;
+        state.seats[0].dice_count = 0;
+        assert_eq!(state.next_seat(0), 2);
+        assert_eq!(state.next_seat(3), 2);
+        assert_eq!(state.active_seats().collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_last_bidder() {
+        let mut state = GameState::new();
+        assert_eq!(state.last_bidder(), None);
+        state.bid_history.push((0, Action::Bid(Bid::new(1, 4))));
+        state.bid_history.push((1, Action::Bid(Bid::new(2, 4))));
+        state.bid_history.push((0, Action::Challenge));
+        assert_eq!(state.last_bidder(), Some(1));
     }
 
     #[test]
     fn test_count_face() {
         let mut state = GameState::new();
-        state.human_dice = vec![1, 2, 3, 4, 5];
-        state.ai_dice = vec![1, 1, 3, 5, 6];
-        assert_eq!(state.count_face(1), 3);
-        assert_eq!(state.count_face(3), 2);
-        assert_eq!(state.count_face(6), 1);
-        assert_eq!(state.count_face(2), 1);
+        state.seats[0].dice = vec![1, 2, 3, 4, 5];
+        state.seats[1].dice = vec![1, 1, 3, 5, 6];
+        assert_eq!(state.count_face(1, false), 3);
+        assert_eq!(state.count_face(3, false), 2);
+        assert_eq!(state.count_face(6, false), 1);
+        assert_eq!(state.count_face(2, false), 1);
+    }
+
+    #[test]
+    fn test_count_face_wild_ones() {
+        let mut state = GameState::with_config(GameConfig {
+            wild_ones: true,
+            ..Default::default()
+        });
+        state.seats[0].dice = vec![1, 2, 3, 4, 5];
+        state.seats[1].dice = vec![1, 1, 3, 5, 6];
+        assert_eq!(state.count_face(3, false), 5);
+        assert_eq!(state.count_wilds(3, false), 3);
+        assert_eq!(state.count_face(6, false), 4);
+        // 叫 1 点时只计 1 点本身
+        assert_eq!(state.count_face(1, false), 3);
+        assert_eq!(state.count_wilds(1, false), 0);
+    }
+
+    #[test]
+    fn test_count_face_pure_cancels_wilds() {
+        let mut state = GameState::with_config(GameConfig {
+            wild_ones: true,
+            ..Default::default()
+        });
+        state.seats[0].dice = vec![1, 2, 3, 4, 5];
+        state.seats[1].dice = vec![1, 1, 3, 5, 6];
+        assert_eq!(state.count_face(3, true), 2);
+        assert_eq!(state.count_wilds(3, true), 0);
+    }
+
+    #[test]
+    fn test_palifico_disables_wilds() {
+        let mut state = GameState::with_config(GameConfig {
+            wild_ones: true,
+            ..Default::default()
+        });
+        state.seats[0].dice = vec![1, 2, 3, 4, 5];
+        state.seats[1].dice = vec![1, 1, 3, 5, 6];
+        state.palifico = Some(1);
+        assert_eq!(state.count_face(3, false), 2);
+        assert_eq!(state.count_wilds(3, false), 0);
+        assert!(state.normalize_bid(Bid::new_pure(2, 3)).is_err());
+        assert_eq!(state.normalize_bid(Bid::new(2, 1)), Ok(Bid::new(2, 1)));
+    }
+
+    #[test]
+    fn test_validate_bid_palifico_locks_face() {
+        let mut state = GameState::new();
+        state.palifico = Some(0);
+        assert_eq!(state.locked_face(), None);
+        assert!(state.validate_bid(Bid::new(1, 4)).is_ok());
+
+        state.current_bid = Some(Bid::new(1, 4));
+        state.bid_history.push((0, Action::Bid(Bid::new(1, 4))));
+        assert_eq!(state.locked_face(), Some(4));
+        assert!(state.validate_bid(Bid::new(2, 5)).is_err());
+        assert!(state.validate_bid(Bid::new(1, 4)).is_err());
+        assert_eq!(state.validate_bid(Bid::new(2, 4)), Ok(Bid::new(2, 4)));
+    }
+
+    #[test]
+    fn test_validate_bid_without_palifico_allows_face_change() {
+        let mut state = GameState::new();
+        state.current_bid = Some(Bid::new(1, 4));
+        state.bid_history.push((0, Action::Bid(Bid::new(1, 4))));
+        assert_eq!(state.locked_face(), None);
+        assert!(state.validate_bid(Bid::new(2, 5)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_bid_bounds_count_by_dice_on_table() {
+        let mut state = GameState::new();
+        assert!(state.validate_bid(Bid::new(0, 3)).is_err());
+        assert!(state.validate_bid(Bid::new(10, 3)).is_ok());
+        assert!(state.validate_bid(Bid::new(11, 3)).is_err());
+
+        // 失骰后上限随之降低
+        state.seats[1].dice_count = 2;
+        assert!(state.validate_bid(Bid::new(8, 3)).is_err());
+        state.current_bid = Some(Bid::new(2, 3));
+        assert!(state.validate_bid(Bid::new(1000, 6)).is_err());
+    }
+
+    #[test]
+    fn test_bid_pure_may_halve_count() {
+        // 飞转斋：数量减半（向上取整）即可
+        let current = Bid::new(5, 4);
+        assert!(current.is_valid_raise(&Bid::new_pure(3, 2)));
+        assert!(!current.is_valid_raise(&Bid::new_pure(2, 6)));
+
+        let current = Bid::new(4, 4);
+        assert!(current.is_valid_raise(&Bid::new_pure(2, 2)));
+    }
+
+    #[test]
+    fn test_bid_normal_over_pure_needs_double_plus_one() {
+        let current = Bid::new_pure(3, 2);
+        assert!(!current.is_valid_raise(&Bid::new(6, 6)));
+        assert!(current.is_valid_raise(&Bid::new(7, 2)));
+    }
+
+    #[test]
+    fn test_bid_pure_over_pure() {
+        let current = Bid::new_pure(3, 4);
+        assert!(current.is_valid_raise(&Bid::new_pure(3, 5)));
+        assert!(current.is_valid_raise(&Bid::new_pure(4, 2)));
+        assert!(!current.is_valid_raise(&Bid::new_pure(3, 3)));
+    }
+
+    #[test]
+    fn test_normalize_bid() {
+        let state = GameState::new();
+        assert!(state.normalize_bid(Bid::new_pure(2, 3)).is_err());
+        assert_eq!(state.normalize_bid(Bid::new(2, 1)), Ok(Bid::new(2, 1)));
+
+        let state = GameState::with_config(GameConfig {
+            wild_ones: true,
+            ..Default::default()
+        });
+        assert_eq!(state.normalize_bid(Bid::new(2, 1)), Ok(Bid::new_pure(2, 1)));
+        assert_eq!(state.normalize_bid(Bid::new(2, 3)), Ok(Bid::new(2, 3)));
     }
 
     #[test]
     fn test_to_view_hides_ai_dice() {
         let state = GameState::new();
-        let view = state.to_view();
-        assert_eq!(view.human_dice.len(), 5);
-        assert_eq!(view.ai_dice_count, 5);
+        let view = state.view_for(0);
+        assert_eq!(view.dice, state.seats[0].dice);
+        assert_eq!(view.seats[1].dice_count, 5);
         assert_eq!(view.current_round, 1);
         assert_eq!(view.max_rounds, 5);
     }
 
     #[test]
-    fn test_has_five_distinct_true() {
-        assert!(GameState::has_five_distinct(&[1, 2, 3, 4, 5]));
+    fn test_view_for_hides_every_other_seat() {
+        let state = GameState::with_config(table(&[
+            PlayerKind::Human,
+            PlayerKind::Human,
+            PlayerKind::AI,
+        ]));
+        for seat in 0..3 {
+            let view = state.view_for(seat);
+            assert_eq!(view.seat, seat);
+            assert_eq!(view.dice, state.seats[seat].dice);
+
+            let json = serde_json::to_value(&view).unwrap();
+            let seats = json["seats"].as_array().unwrap();
+            assert!(seats.iter().all(|s| s.get("dice").is_none()));
+        }
+    }
+
+    #[test]
+    fn test_observation_cannot_reach_opponent_dice() {
+        let mut state =
+            GameState::with_config(table(&[PlayerKind::Human, PlayerKind::AI, PlayerKind::AI]));
+        state.seats[1].dice = vec![2, 3, 3, 5, 6];
+        state.bid_history.push((0, Action::Bid(Bid::new(2, 3))));
+        state.current_bid = Some(Bid::new(2, 3));
+
+        // 只改对手的骰子，观察完全不变：观察中不含对手骰子的任何信息
+        let mut other = state.clone();
+        other.seats[0].dice = vec![6, 6, 6, 6, 6];
+        other.seats[2].dice = vec![1, 1, 1, 1, 1];
+        let observation = state.observation_for(1);
+        assert_eq!(observation, other.observation_for(1));
+        assert_eq!(observation.dice, vec![2, 3, 3, 5, 6]);
+        assert_eq!(observation.seats[0].dice_count, 5);
+        assert_eq!(observation.total_dice(), 15);
+        assert_eq!(observation.opponents().collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(
+            observation.validate_bid(Bid::new(2, 3)),
+            state.validate_bid(Bid::new(2, 3))
+        );
+
+        // 有种子的对局：观察里没有种子，无法按种子重掷出对手的骰子
+        let mut config = table(&[PlayerKind::Human, PlayerKind::AI, PlayerKind::AI]);
+        config.seed = Some(9);
+        let seeded = GameState::with_config(config.clone());
+        let observation = seeded.observation_for(1);
+        assert_eq!(observation.config.seed, None);
+        let mut reseeded = seeded.clone();
+        config.seed = Some(10);
+        reseeded.config = config;
+        reseeded.seed = Some(10);
+        reseeded.seats[0].dice = vec![4, 4, 4, 4, 4];
+        assert_eq!(observation, reseeded.observation_for(1));
     }
 
     #[test]
-    fn test_has_five_distinct_false_with_duplicate() {
-        assert!(!GameState::has_five_distinct(&[1, 2, 2, 4, 5]));
+    fn test_review_bids_flags_bluffs() {
+        let mut state = GameState::with_config(GameConfig {
+            wild_ones: true,
+            ..Default::default()
+        });
+        state.seats[0].dice = vec![2, 2, 3, 5, 6];
+        state.seats[1].dice = vec![1, 4, 4, 5, 6];
+        state.bid_history = vec![
+            (0, Action::Bid(Bid::new(2, 4))),
+            (1, Action::Bid(Bid::new(3, 2))),
+            (0, Action::Bid(Bid::new_pure(2, 4))),
+            (1, Action::Challenge),
+        ];
+
+        let reviews = state.review_bids();
+        let flags: Vec<(usize, u32, bool)> =
+            reviews.iter().map(|r| (r.seat, r.held, r.bluff)).collect();
+        // 1 号座位的百搭 1 点计入 2 点，不算诈叫
+        assert_eq!(flags, vec![(0, 0, true), (1, 1, false), (0, 0, true)]);
     }
 
     #[test]
-    fn test_has_five_distinct_false_non_five_len() {
-        assert!(!GameState::has_five_distinct(&[1, 2, 3, 4]));
+    fn test_review_scores_decisions_from_own_view() {
+        let mut state = GameState::with_config(GameConfig {
+            wild_ones: true,
+            ..Default::default()
+        });
+        state.seats[0].dice = vec![2, 2, 3, 5, 6];
+        state.seats[1].dice = vec![1, 4, 4, 5, 6];
+        state.bid_history = vec![
+            (0, Action::Bid(Bid::new(2, 4))),
+            (1, Action::Bid(Bid::new_pure(2, 4))),
+        ];
+        state.current_bid = Some(Bid::new_pure(2, 4));
+
+        // 0 号座位没有 4 也没有 1，要靠对手 5 颗骰子中至少 2 个 4 或 1
+        let pmf = crate::game::odds::hand_count_pmf(5, 6, 2, true);
+        let reviews = state.review_bids();
+        assert!((reviews[0].probability - (1.0 - pmf[0] - pmf[1])).abs() < 1e-12);
+        assert_eq!(reviews[1].probability, 1.0);
+
+        // 1 号座位自己就有 2 个 4，开必输；定则要对手恰好没有 4
+        let call = state.review_call(1, false);
+        assert_eq!(call.probability, 1.0);
+        assert_eq!(call.expected_value, -1.0);
+        let exact = crate::game::odds::hand_count_pmf(5, 6, 1, true)[0];
+        assert!((call.exact_probability - exact).abs() < 1e-12);
+        assert!((call.alternative_value - spot_on_value(exact)).abs() < 1e-12);
+        assert!(call.alternative_value > call.expected_value);
+    }
+
+    #[test]
+    fn test_game_config_rejects_bad_bluff_rate() {
+        let mut config = GameConfig::default();
+        config.players[1].bluff_rate = Some(1.5);
+        assert!(config.validate().is_err());
+        config.players[1].bluff_rate = Some(0.3);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_is_straight_true() {
+        assert!(GameState::is_straight(
+            &GameConfig::default(),
+            &[1, 2, 3, 4, 5]
+        ));
+    }
+
+    #[test]
+    fn test_is_straight_false_with_duplicate() {
+        assert!(!GameState::is_straight(
+            &GameConfig::default(),
+            &[1, 2, 2, 4, 5]
+        ));
+    }
+
+    #[test]
+    fn test_is_straight_false_short_hand() {
+        assert!(!GameState::is_straight(
+            &GameConfig::default(),
+            &[1, 2, 3, 4]
+        ));
+    }
+
+    #[test]
+    fn test_is_straight_respects_die_sides() {
+        let config = GameConfig {
+            die_sides: 8,
+            ..Default::default()
+        };
+        assert!(GameState::is_straight(&config, &[2, 4, 6, 7, 8]));
+        assert!(!GameState::is_straight(&config, &[2, 4, 6, 7, 9]));
+    }
+
+    #[test]
+    fn test_small_hands_are_never_straights() {
+        let config = GameConfig {
+            dice_per_player: 2,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        let config = GameConfig {
+            reroll_straights: false,
+            ..config
+        };
+        assert!(config.validate().is_ok());
+        assert!(!GameState::is_straight(&config, &[1, 2]));
+
+        // 两颗骰子各不相同是常态，不会被重掷成对子
+        let mut call_count = 0;
+        let rolled = GameState::roll_dice_with_auto_reroll_using(&config, || {
+            call_count += 1;
+            vec![3, 5]
+        });
+        assert_eq!((call_count, rolled), (1, vec![3, 5]));
     }
 
     #[test]
     fn test_roll_dice_with_auto_reroll_until_not_distinct() {
         let mut call_count = 0;
-        let rolled = GameState::roll_dice_with_auto_reroll_using(5, || {
+        let rolled = GameState::roll_dice_with_auto_reroll_using(&GameConfig::default(), || {
             call_count += 1;
             if call_count == 1 {
                 vec![1, 2, 3, 4, 5]
@@ -283,7 +1454,7 @@ mod tests {
     #[test]
     fn test_roll_dice_with_auto_reroll_no_reroll_when_not_five_dice() {
         let mut call_count = 0;
-        let rolled = GameState::roll_dice_with_auto_reroll_using(4, || {
+        let rolled = GameState::roll_dice_with_auto_reroll_using(&GameConfig::default(), || {
             call_count += 1;
             vec![1, 2, 3, 4]
         });
@@ -291,4 +1462,37 @@ mod tests {
         assert_eq!(call_count, 1);
         assert_eq!(rolled, vec![1, 2, 3, 4]);
     }
+
+    #[test]
+    fn test_roll_dice_without_reroll_rule_keeps_straight() {
+        let config = GameConfig {
+            reroll_straights: false,
+            ..Default::default()
+        };
+        let mut call_count = 0;
+        let rolled = GameState::roll_dice_with_auto_reroll_using(&config, || {
+            call_count += 1;
+            vec![1, 2, 3, 4, 5]
+        });
+
+        assert_eq!(call_count, 1);
+        assert_eq!(rolled, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_with_config_uses_dice_and_rounds() {
+        let state = GameState::with_config(GameConfig {
+            dice_per_player: 3,
+            die_sides: 8,
+            max_rounds: 7,
+            ..Default::default()
+        });
+        assert_eq!(state.max_rounds, 7);
+        for seat in &state.seats {
+            assert_eq!(seat.dice_count, 3);
+            assert_eq!(seat.dice.len(), 3);
+            assert!(seat.dice.iter().all(|&d| (1..=8).contains(&d)));
+        }
+        assert!(state.validate_bid(Bid::new(1, 8)).is_ok());
+    }
 }
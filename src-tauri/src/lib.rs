@@ -1,9 +1,13 @@
 mod commands;
-mod game;
+pub mod game;
 
 use commands::AppState;
 use game::engine::GameEngine;
 use std::sync::Mutex;
+use tauri::Manager;
+
+/// 人类玩家档案在应用数据目录下的文件名
+const PROFILES_FILE: &str = "profiles.json";
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -12,12 +16,26 @@ pub fn run() {
         .manage(AppState {
             engine: Mutex::new(GameEngine::new()),
         })
+        .setup(|app| {
+            let path = app.path().app_data_dir()?.join(PROFILES_FILE);
+            let state = app.state::<AppState>();
+            let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+            // 档案损坏不应让应用无法启动：以空档案继续，问题随视图报告给前端
+            engine.load_profiles(path);
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::start_game,
             commands::player_bid,
             commands::player_challenge,
+            commands::player_spot_on,
             commands::get_game_state,
             commands::next_round,
+            commands::get_hint,
+            commands::get_match_report,
+            commands::get_profile,
+            commands::list_personalities,
+            commands::calculate_odds,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
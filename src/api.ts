@@ -1,22 +1,60 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { GameView } from "./types";
+import type {
+  GameConfig,
+  GameView,
+  Hint,
+  MatchReport,
+  Odds,
+  OddsQuery,
+  PersonalityInfo,
+  PlayerProfile,
+} from "./types";
 
-export async function startGame(): Promise<GameView> {
-  return invoke<GameView>("start_game");
+export async function startGame(config?: GameConfig): Promise<GameView> {
+  return invoke<GameView>("start_game", { config: config ?? null });
 }
 
-export async function playerBid(count: number, face: number): Promise<GameView> {
-  return invoke<GameView>("player_bid", { count, face });
+export async function playerBid(
+  seat: number,
+  count: number,
+  face: number,
+  pure = false,
+): Promise<GameView> {
+  return invoke<GameView>("player_bid", { seat, count, face, pure });
 }
 
-export async function playerChallenge(): Promise<GameView> {
-  return invoke<GameView>("player_challenge");
+export async function playerChallenge(seat: number): Promise<GameView> {
+  return invoke<GameView>("player_challenge", { seat });
 }
 
-export async function getGameState(): Promise<GameView> {
-  return invoke<GameView>("get_game_state");
+export async function playerSpotOn(seat: number): Promise<GameView> {
+  return invoke<GameView>("player_spot_on", { seat });
 }
 
-export async function nextRound(): Promise<GameView> {
-  return invoke<GameView>("next_round");
+export async function getGameState(seat?: number): Promise<GameView> {
+  return invoke<GameView>("get_game_state", { seat: seat ?? null });
+}
+
+export async function getHint(seat?: number): Promise<Hint> {
+  return invoke<Hint>("get_hint", { seat: seat ?? null });
+}
+
+export async function getMatchReport(): Promise<MatchReport> {
+  return invoke<MatchReport>("get_match_report");
+}
+
+export async function nextRound(seat: number): Promise<GameView> {
+  return invoke<GameView>("next_round", { seat });
+}
+
+export async function getProfile(name: string): Promise<PlayerProfile | null> {
+  return invoke<PlayerProfile | null>("get_profile", { name });
+}
+
+export async function listPersonalities(): Promise<PersonalityInfo[]> {
+  return invoke<PersonalityInfo[]>("list_personalities");
+}
+
+export async function calculateOdds(query: OddsQuery): Promise<Odds> {
+  return invoke<Odds>("calculate_odds", { query });
 }
@@ -1,10 +1,13 @@
-import type { Action, Player } from "../types";
+import type { Action, SeatView, Taunt } from "../types";
 
 interface BidHistoryProps {
-  history: [Player, Action][];
+  history: [number, Action][];
+  taunts: Taunt[];
+  seats: SeatView[];
+  viewer: number;
 }
 
-export default function BidHistory({ history }: BidHistoryProps) {
+export default function BidHistory({ history, taunts, seats, viewer }: BidHistoryProps) {
   if (history.length === 0) {
     return (
       <section className="flex h-full flex-col justify-center rounded-xl border border-dashed border-amber-100/20 bg-slate-950/35 p-4 text-center">
@@ -27,13 +30,16 @@ export default function BidHistory({ history }: BidHistoryProps) {
       </div>
 
       <div className="scroll-thin max-h-[21rem] space-y-2 overflow-y-auto pr-1">
-        {history.map(([player, action], index) => {
-          const isHuman = player === "Human";
-          const label = isHuman ? "You" : "AI";
+        {history.map(([seat, action], index) => {
+          const isHuman = seat === viewer;
+          const label = isHuman ? "You" : seats[seat]?.name ?? `Seat ${seat + 1}`;
           const text =
             action === "Challenge"
               ? "called challenge"
-              : `bid ${action.Bid.count} x face ${action.Bid.face}`;
+              : action === "SpotOn"
+                ? "called spot on"
+                : `bid ${action.Bid.count} x face ${action.Bid.face}${action.Bid.pure ? " pure" : ""}`;
+          const taunt = taunts.find((t) => t.action === index);
 
           return (
             <div
@@ -49,6 +55,7 @@ export default function BidHistory({ history }: BidHistoryProps) {
                 <span className="text-xs opacity-75">#{index + 1}</span>
               </div>
               <p className="mt-1 text-sm">{text}</p>
+              {taunt && <p className="mt-1 text-xs italic opacity-80">"{taunt.line}"</p>}
             </div>
           );
         })}
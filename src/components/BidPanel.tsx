@@ -4,23 +4,34 @@ import type { Bid } from "../types";
 interface BidPanelProps {
   currentBid: Bid | null;
   totalDice: number;
-  onBid: (count: number, face: number) => void;
+  dieSides: number;
+  wildOnes: boolean;
+  lockedFace: number | null;
+  onBid: (count: number, face: number, pure: boolean) => void;
   onChallenge: () => void;
+  onSpotOn: () => void;
   disabled: boolean;
 }
 
 export default function BidPanel({
   currentBid,
   totalDice,
+  dieSides,
+  wildOnes,
+  lockedFace,
   onBid,
   onChallenge,
+  onSpotOn,
   disabled,
 }: BidPanelProps) {
   const nextCount = currentBid ? currentBid.count : 1;
-  const nextFace = currentBid ? Math.min(6, currentBid.face + 1) : 1;
+  const nextFace = lockedFace ?? (currentBid ? Math.min(dieSides, currentBid.face + 1) : 1);
 
   const [count, setCount] = useState(nextCount);
   const [face, setFace] = useState(nextFace);
+  const [pureChoice, setPureChoice] = useState(false);
+  // Mirrors the engine: pure bids need wild ones, and a bid on 1s is always pure.
+  const pure = wildOnes && (pureChoice || face === 1);
 
   useEffect(() => {
     setCount(nextCount);
@@ -28,26 +39,44 @@ export default function BidPanel({
   }, [nextCount, nextFace]);
 
   const isValidBid = useMemo(() => {
-    if (face < 1 || face > 6 || count < 1 || count > totalDice) {
+    if (face < 1 || face > dieSides || count < 1 || count > totalDice) {
+      return false;
+    }
+    if (lockedFace !== null && face !== lockedFace) {
       return false;
     }
     if (!currentBid) {
       return true;
     }
-    return count > currentBid.count || (count === currentBid.count && face > currentBid.face);
-  }, [count, currentBid, face, totalDice]);
+    // Same ordering as Bid::is_valid_raise: pure counts double, pure beats normal on ties.
+    const key = (bid: Bid): [number, number, number] => [
+      bid.pure ? bid.count * 2 : bid.count,
+      bid.pure ? 1 : 0,
+      bid.face,
+    ];
+    const next = key({ count, face, pure });
+    const current = key(currentBid);
+    for (let i = 0; i < next.length; i += 1) {
+      if (next[i] !== current[i]) {
+        return next[i] > current[i];
+      }
+    }
+    return false;
+  }, [count, currentBid, dieSides, face, lockedFace, pure, totalDice]);
 
   return (
     <section className="panel p-4 md:p-5">
       <div className="flex flex-col gap-3 md:flex-row md:items-start md:justify-between">
         <div>
           <p className="section-label">Your Action</p>
-          <h3 className="title-font text-2xl text-amber-100">Raise or Challenge</h3>
+          <h3 className="title-font text-2xl text-amber-100">Raise, Challenge or Spot On</h3>
         </div>
         <div className="rounded-xl border border-amber-100/20 bg-slate-950/45 px-3 py-2 text-right">
           <p className="text-xs uppercase tracking-[0.16em] text-amber-100/75">Active Bid</p>
           <p className="mt-1 text-sm text-slate-100">
-            {currentBid ? `${currentBid.count} x face ${currentBid.face}` : "No bid yet"}
+            {currentBid
+              ? `${currentBid.count} x face ${currentBid.face}${currentBid.pure ? " pure" : ""}`
+              : "No bid yet"}
           </p>
         </div>
       </div>
@@ -84,24 +113,35 @@ export default function BidPanel({
         <div className="rounded-xl border border-amber-100/15 bg-slate-900/45 p-4">
           <p className="section-label">Face Value</p>
           <div className="mt-3 grid grid-cols-6 gap-2">
-            {[1, 2, 3, 4, 5, 6].map((value) => (
+            {Array.from({ length: dieSides }, (_, i) => i + 1).map((value) => (
               <button
                 key={value}
                 className={`face-button h-10 ${face === value ? "face-button-active" : ""}`}
                 onClick={() => setFace(value)}
-                disabled={disabled}
+                disabled={disabled || (lockedFace !== null && value !== lockedFace)}
               >
                 {value}
               </button>
             ))}
           </div>
+          {wildOnes ? (
+            <label className="mt-3 flex items-center gap-2 text-xs text-slate-300">
+              <input
+                type="checkbox"
+                checked={pure}
+                onChange={(event) => setPureChoice(event.target.checked)}
+                disabled={disabled || face === 1}
+              />
+              Pure bid: 1s are not wild for this call.
+            </label>
+          ) : null}
         </div>
       </div>
 
       <div className="mt-5 flex flex-wrap gap-3">
         <button
           className="primary-btn"
-          onClick={() => onBid(count, face)}
+          onClick={() => onBid(count, face, pure)}
           disabled={disabled || !isValidBid}
         >
           Place Bid
@@ -112,6 +152,12 @@ export default function BidPanel({
             Challenge
           </button>
         )}
+
+        {currentBid && (
+          <button className="muted-btn" onClick={onSpotOn} disabled={disabled}>
+            Spot On
+          </button>
+        )}
       </div>
     </section>
   );
@@ -26,7 +26,19 @@ function Die({ value, hidden, index }: { value: number; hidden?: boolean; index:
     );
   }
 
-  const dots = dotPositions[value] || [];
+  const dots = dotPositions[value];
+  if (!dots) {
+    // Faces beyond six have no pip layout; show the number instead.
+    return (
+      <div
+        className="die-base flex items-center justify-center font-semibold"
+        style={{ animationDelay: `${index * 45}ms` }}
+      >
+        {value}
+      </div>
+    );
+  }
+
   return (
     <div className="die-base" style={{ animationDelay: `${index * 45}ms` }}>
       {dots.map(([x, y], i) => (
@@ -1,6 +1,6 @@
 import { useCallback, useEffect, useState } from "react";
-import type { GameView, Player, RoundResult } from "../types";
-import { nextRound, playerBid, playerChallenge, startGame } from "../api";
+import type { Action, GameView, Hint, MatchReport, RoundResult } from "../types";
+import { getHint, getMatchReport, nextRound, playerBid, playerChallenge, playerSpotOn, startGame } from "../api";
 import BidHistory from "./BidHistory";
 import BidPanel from "./BidPanel";
 import DiceView from "./DiceView";
@@ -11,6 +11,20 @@ export default function GameBoard() {
   const [gameView, setGameView] = useState<GameView | null>(null);
   const [error, setError] = useState<string | null>(null);
   const [loading, setLoading] = useState(false);
+  const [hint, setHint] = useState<Hint | null>(null);
+  const [report, setReport] = useState<MatchReport | null>(null);
+  const seat = gameView?.seat ?? 0;
+
+  useEffect(() => {
+    setHint(null);
+    if (gameView && "GameOver" in gameView.phase) {
+      getMatchReport()
+        .then(setReport)
+        .catch((err) => setError(String(err)));
+    } else {
+      setReport(null);
+    }
+  }, [gameView]);
 
   const handleStartGame = useCallback(async () => {
     try {
@@ -29,11 +43,11 @@ export default function GameBoard() {
     handleStartGame();
   }, [handleStartGame]);
 
-  const handleBid = async (count: number, face: number) => {
+  const handleBid = async (count: number, face: number, pure: boolean) => {
     try {
       setLoading(true);
       setError(null);
-      const view = await playerBid(count, face);
+      const view = await playerBid(seat, count, face, pure);
       setGameView(view);
     } catch (err) {
       setError(String(err));
@@ -46,7 +60,20 @@ export default function GameBoard() {
     try {
       setLoading(true);
       setError(null);
-      const view = await playerChallenge();
+      const view = await playerChallenge(seat);
+      setGameView(view);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  const handleSpotOn = async () => {
+    try {
+      setLoading(true);
+      setError(null);
+      const view = await playerSpotOn(seat);
       setGameView(view);
     } catch (err) {
       setError(String(err));
@@ -55,11 +82,20 @@ export default function GameBoard() {
     }
   };
 
+  const handleHint = async () => {
+    try {
+      setError(null);
+      setHint(await getHint(seat));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
   const handleNextRound = async () => {
     try {
       setLoading(true);
       setError(null);
-      const view = await nextRound();
+      const view = await nextRound(seat);
       setGameView(view);
     } catch (err) {
       setError(String(err));
@@ -78,11 +114,11 @@ export default function GameBoard() {
   }
 
   let roundResult: RoundResult | null = null;
-  let gameOverWinner: { winner: Player } | null = null;
+  let gameOverWinner: { winner: number | null } | null = null;
   let isPlayerTurn = false;
 
-  if (typeof gameView.phase === "string") {
-    isPlayerTurn = gameView.phase === "PlayerTurn";
+  if ("Turn" in gameView.phase) {
+    isPlayerTurn = gameView.phase.Turn === gameView.seat;
   } else if ("RoundOver" in gameView.phase) {
     roundResult = gameView.phase.RoundOver;
   } else if ("GameOver" in gameView.phase) {
@@ -90,7 +126,11 @@ export default function GameBoard() {
     roundResult = gameView.last_round_result;
   }
 
-  const totalDice = gameView.human_dice_count + gameView.ai_dice_count;
+  const totalDice = gameView.seats.reduce((sum, player) => sum + player.dice_count, 0);
+  const opponents = gameView.seats
+    .map((player, index) => ({ ...player, index }))
+    .filter((player) => player.index !== gameView.seat);
+  const diceLoss = gameView.config.format === "DiceLoss";
   const statusClass = gameOverWinner
     ? "status-danger"
     : isPlayerTurn
@@ -115,6 +155,9 @@ export default function GameBoard() {
               <p className="section-label">Casino Table</p>
               <h1 className="title-font text-4xl text-amber-100 md:text-5xl">Liar&apos;s Dice</h1>
               <p className="mt-1 text-sm text-slate-300">Read the tells. Press the odds. Time the challenge.</p>
+              <p className="mt-1 text-xs uppercase tracking-[0.16em] text-amber-100/70">
+                AI difficulty: {gameView.difficulty}
+              </p>
             </div>
 
             <div className="flex flex-col items-start gap-2 md:items-end">
@@ -123,7 +166,7 @@ export default function GameBoard() {
                 <p className="text-xs uppercase tracking-[0.16em] text-amber-100/70">Current Bid</p>
                 <p className="mt-1 text-sm text-slate-100">
                   {gameView.current_bid
-                    ? `${gameView.current_bid.count} x face ${gameView.current_bid.face}`
+                    ? `${gameView.current_bid.count} x face ${gameView.current_bid.face}${gameView.current_bid.pure ? " pure" : ""}`
                     : "No bid yet"}
                 </p>
               </div>
@@ -133,24 +176,40 @@ export default function GameBoard() {
 
         <ScoreBoard
           currentRound={gameView.current_round}
-          maxRounds={gameView.max_rounds}
-          humanWins={gameView.human_wins}
-          aiWins={gameView.ai_wins}
+          maxRounds={diceLoss ? null : gameView.max_rounds}
+          seats={gameView.seats}
+          rounds={gameView.rounds}
+          viewer={gameView.seat}
         />
 
         <section className="grid gap-4 xl:grid-cols-[1.06fr_1fr_1.06fr]">
           <article className="panel flex flex-col gap-4 p-4 md:p-5">
-            <DiceView dice={[]} hidden count={gameView.ai_dice_count} label="Opponent Dice" />
+            {opponents.map((player) => (
+              <DiceView
+                key={player.index}
+                dice={[]}
+                hidden
+                count={player.dice_count}
+                label={`${player.name} Dice`}
+              />
+            ))}
             <div className="glass-divider" />
-            <p className="text-center text-xs text-slate-400">AI dice remain hidden until a challenge resolves.</p>
+            <p className="text-center text-xs text-slate-400">
+              Opponent dice remain hidden until a challenge resolves.
+            </p>
           </article>
 
           <article className="panel p-4 md:p-5">
-            <BidHistory history={gameView.bid_history} />
+            <BidHistory
+              history={gameView.bid_history}
+              taunts={gameView.taunts}
+              seats={gameView.seats}
+              viewer={gameView.seat}
+            />
           </article>
 
           <article className="panel flex flex-col gap-4 p-4 md:p-5">
-            <DiceView dice={gameView.human_dice} label="Your Dice" />
+            <DiceView dice={gameView.dice} label="Your Dice" />
             <div className="glass-divider" />
             <p className="text-center text-xs text-slate-400">Dice in play: {totalDice}</p>
           </article>
@@ -160,25 +219,86 @@ export default function GameBoard() {
           <BidPanel
             currentBid={gameView.current_bid}
             totalDice={totalDice}
+            dieSides={gameView.config.die_sides}
+            wildOnes={gameView.config.wild_ones && gameView.palifico === null}
+            lockedFace={gameView.locked_face}
             onBid={handleBid}
             onChallenge={handleChallenge}
+            onSpotOn={handleSpotOn}
             disabled={!isPlayerTurn || loading || gameOverWinner !== null}
           />
 
           <aside className="panel flex flex-col gap-3 p-4 md:p-5">
             <p className="section-label">Table Notes</p>
             <div className="rounded-xl border border-amber-100/15 bg-slate-950/35 p-3 text-sm text-slate-300">
-              <p>Every round uses five dice each. No attrition, no carry-over.</p>
+              <p>
+                {diceLoss
+                  ? "The round loser gives up a die. Run out of dice and the match is over."
+                  : `Every round uses ${gameView.config.dice_per_player} d${gameView.config.die_sides} per seat. No attrition, no carry-over.`}
+              </p>
+            </div>
+            {gameView.palifico !== null ? (
+              <div className="rounded-xl border border-amber-100/15 bg-slate-950/35 p-3 text-sm text-slate-300">
+                <p>
+                  Palifico round:{" "}
+                  {gameView.palifico === gameView.seat
+                    ? "you are"
+                    : `${gameView.seats[gameView.palifico].name} is`}{" "}
+                  down to one die.
+                  Wilds are off and the opening face is locked.
+                </p>
+              </div>
+            ) : null}
+            <div className="rounded-xl border border-amber-100/15 bg-slate-950/35 p-3 text-sm text-slate-300">
+              <p>
+                {gameView.opener === gameView.seat
+                  ? "You opened this round."
+                  : `${gameView.seats[gameView.opener].name} opened this round.`}
+              </p>
             </div>
             <div className="rounded-xl border border-amber-100/15 bg-slate-950/35 p-3 text-sm text-slate-300">
               <p>Challenge when the quantity feels inflated against visible pressure.</p>
+              <button
+                type="button"
+                className="mt-2 rounded-lg border border-amber-100/25 px-2 py-1 text-xs text-amber-100 disabled:opacity-40"
+                onClick={handleHint}
+                disabled={!isPlayerTurn || loading}
+              >
+                Show hint
+              </button>
+              {hint ? (
+                <div className="mt-2 space-y-1 text-xs">
+                  {hint.bid_probability !== null ? (
+                    <p>
+                      Current bid holds: {percent(hint.bid_probability)} (exactly: {percent(hint.exact_probability ?? 0)})
+                    </p>
+                  ) : null}
+                  {hint.raises.map((raise, index) => (
+                    <p key={index}>
+                      {raise.bid.count} x face {raise.bid.face}
+                      {raise.bid.pure ? " pure" : ""}: {percent(raise.probability)}
+                    </p>
+                  ))}
+                  <p className="text-amber-100">Suggested: {describeAction(hint.recommended)}</p>
+                </div>
+              ) : null}
             </div>
             <div className="rounded-xl border border-amber-100/15 bg-slate-950/35 p-3 text-sm text-slate-300">
-              <p>Final winner is the side with more round wins after {gameView.max_rounds} rounds.</p>
+              <p>
+                {diceLoss
+                  ? "Final winner is the last seat with dice on the table."
+                  : `Final winner is the seat with the most round wins after ${gameView.max_rounds} rounds.`}
+              </p>
             </div>
           </aside>
         </section>
 
+        {gameView.profile_warning ? (
+          <div className="rounded-xl border border-amber-300/35 bg-amber-500/15 px-4 py-3 text-sm text-amber-100">
+            {gameView.profile_warning}
+          </div>
+        ) : null}
+
         {error ? (
           <div className="rounded-xl border border-rose-400/35 bg-rose-500/18 px-4 py-3 text-sm text-rose-100">
             {error}
@@ -196,13 +316,24 @@ export default function GameBoard() {
       <ResultModal
         result={roundResult}
         gameOver={gameOverWinner}
+        report={report}
         currentRound={gameView.current_round}
-        maxRounds={gameView.max_rounds}
-        humanWins={gameView.human_wins}
-        aiWins={gameView.ai_wins}
+        maxRounds={diceLoss ? null : gameView.max_rounds}
+        seats={gameView.seats}
+        viewer={gameView.seat}
         onNextRound={handleNextRound}
         onNewGame={handleStartGame}
       />
     </div>
   );
 }
+
+function percent(probability: number): string {
+  return `${Math.round(probability * 100)}%`;
+}
+
+function describeAction(action: Action): string {
+  if (action === "Challenge") return "challenge";
+  if (action === "SpotOn") return "call spot on";
+  return `bid ${action.Bid.count} x face ${action.Bid.face}${action.Bid.pure ? " pure" : ""}`;
+}
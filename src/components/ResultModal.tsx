@@ -1,13 +1,14 @@
-import type { Player, RoundResult } from "../types";
+import type { MatchReport, RoundResult, SeatView } from "../types";
 import DiceView from "./DiceView";
 
 interface ResultModalProps {
   result: RoundResult | null;
-  gameOver: { winner: Player } | null;
+  gameOver: { winner: number | null } | null;
+  report: MatchReport | null;
   currentRound: number;
-  maxRounds: number;
-  humanWins: number;
-  aiWins: number;
+  maxRounds: number | null;
+  seats: SeatView[];
+  viewer: number;
   onNextRound: () => void;
   onNewGame: () => void;
 }
@@ -15,10 +16,11 @@ interface ResultModalProps {
 export default function ResultModal({
   result,
   gameOver,
+  report,
   currentRound,
   maxRounds,
-  humanWins,
-  aiWins,
+  seats,
+  viewer,
   onNextRound,
   onNewGame,
 }: ResultModalProps) {
@@ -27,15 +29,19 @@ export default function ResultModal({
   }
 
   const isGameOver = gameOver !== null;
-  const winner = gameOver?.winner ?? result?.winner;
-  const title =
-    winner === "Human"
+  const isDraw = gameOver !== null && gameOver.winner === null;
+  const winner = gameOver ? gameOver.winner : result?.winner;
+  const winnerName = winner != null ? seats[winner]?.name ?? "Opponent" : "Opponent";
+  const suddenDeath = maxRounds !== null && currentRound >= maxRounds;
+  const title = isDraw
+    ? "Match Drawn"
+    : winner === viewer
       ? isGameOver
         ? "You Own The Table"
         : "Round Secured"
       : isGameOver
-        ? "House Wins The Match"
-        : "AI Takes The Round";
+        ? `${winnerName} Wins The Match`
+        : `${winnerName} Takes The Round`;
 
   return (
     <div className="fixed inset-0 z-50 flex items-center justify-center bg-[#05080e]/80 p-4 backdrop-blur-sm">
@@ -44,7 +50,9 @@ export default function ResultModal({
           <div>
             <p className="section-label">Round Resolution</p>
             <h2
-              className={`title-font text-3xl ${winner === "Human" ? "text-emerald-200" : "text-rose-200"}`}
+              className={`title-font text-3xl ${
+                isDraw ? "text-amber-100" : winner === viewer ? "text-emerald-200" : "text-rose-200"
+              }`}
             >
               {title}
             </h2>
@@ -52,7 +60,7 @@ export default function ResultModal({
           <div className="rounded-xl border border-amber-100/20 bg-slate-950/45 px-3 py-2 text-right">
             <p className="text-xs uppercase tracking-[0.16em] text-amber-100/70">Match Score</p>
             <p className="mt-1 text-sm text-slate-100">
-              You {humanWins} : {aiWins} AI
+              {seats.map((seat) => seat.wins).join(" : ")}
             </p>
           </div>
         </div>
@@ -61,26 +69,118 @@ export default function ResultModal({
           <div className="mt-4 rounded-xl border border-amber-100/15 bg-slate-950/40 p-3 text-sm text-slate-200">
             <p>
               Final bid: {result.last_bid.count} x face {result.last_bid.face}
+              {result.last_bid.pure ? " pure" : ""}
+            </p>
+            <p className="mt-1 text-slate-300">
+              Actual count on table: {result.actual_count}
+              {result.wild_count > 0 ? ` (${result.wild_count} wild)` : ""}
+            </p>
+            {result.spot_on ? (
+              <p className="mt-1 text-slate-300">
+                Spot on {result.actual_count === result.last_bid.count ? "hit" : "missed"}: worth{" "}
+                {result.stake} {result.stake === 1 ? "win" : "wins"}.
+              </p>
+            ) : null}
+            <p className="mt-1 text-xs text-slate-400">
+              Round {result.round} complete.{result.seed !== null ? ` Replay seed ${result.seed}.` : ""}
             </p>
-            <p className="mt-1 text-slate-300">Actual count on table: {result.actual_count}</p>
-            <p className="mt-1 text-xs text-slate-400">Round {result.round} complete.</p>
+          </div>
+        ) : null}
+
+        {result ? (
+          <div className="mt-4 rounded-xl border border-amber-100/15 bg-slate-950/40 p-3 text-sm text-slate-200">
+            <p className="section-label">Call Review</p>
+            <p className="mt-2">
+              {result.call.seat === viewer ? "You" : seats[result.call.seat]?.name ?? "Opponent"}{" "}
+              {result.spot_on ? "called spot on" : "challenged"} believing the bid held{" "}
+              {percent(result.call.probability)} of the time (exactly: {percent(result.call.exact_probability)}).
+            </p>
+            <p className="mt-1 text-slate-300">
+              Expected value {signed(result.call.expected_value)}; {result.spot_on ? "a challenge" : "spot on"} was worth{" "}
+              {signed(result.call.alternative_value)}.
+              {result.call.expected_value < 0 ? " On the caller's own information it was more likely to lose than win." : ""}
+            </p>
+          </div>
+        ) : null}
+
+        {result && result.bids.length > 0 ? (
+          <div className="mt-4 rounded-xl border border-amber-100/15 bg-slate-950/40 p-3 text-sm">
+            <p className="section-label">Bid Review</p>
+            <ul className="mt-2 space-y-1">
+              {result.bids.map((review, index) => (
+                <li key={index} className="flex items-center justify-between gap-3 text-slate-200">
+                  <span>
+                    {review.seat === viewer ? "You" : seats[review.seat]?.name ?? "Opponent"}: {review.bid.count} x face{" "}
+                    {review.bid.face}
+                    {review.bid.pure ? " pure" : ""}
+                  </span>
+                  <span className={review.bluff ? "text-rose-200" : "text-slate-400"}>
+                    {review.bluff ? "Bluff" : `Held ${review.held}`} · {percent(review.probability)} to hold
+                  </span>
+                </li>
+              ))}
+            </ul>
+          </div>
+        ) : null}
+
+        {report ? (
+          <div className="mt-4 rounded-xl border border-amber-100/15 bg-slate-950/40 p-3 text-sm">
+            <p className="section-label">Match Report</p>
+            <ul className="mt-2 space-y-2">
+              {report.players.map((player) => (
+                <li key={player.seat} className="text-slate-200">
+                  <p>{player.seat === viewer ? "You" : player.name}</p>
+                  <p className="text-xs text-slate-400">
+                    Bluffs {player.bluffs}/{player.bids}
+                    {player.bluff_rate !== null ? ` (${percent(player.bluff_rate)})` : ""} · Calls right{" "}
+                    {player.correct_calls}/{player.calls} · Bids defended {player.bids_defended}/{player.bids_called}
+                  </p>
+                  <p className="text-xs text-slate-400">
+                    Skill: call value {signed(player.expected_call_value)} expected,{" "}
+                    {signed(player.actual_call_value)} actual · Dice luck{" "}
+                    {player.dice_luck !== null ? signed(player.dice_luck) : "n/a"} per round
+                  </p>
+                </li>
+              ))}
+            </ul>
+            {report.riskiest_bid ? (
+              <p className="mt-2 text-xs text-slate-300">
+                Riskiest bid: {report.riskiest_bid.review.seat === viewer
+                  ? "You"
+                  : seats[report.riskiest_bid.review.seat]?.name ?? "Opponent"}{" "}
+                bid {report.riskiest_bid.review.bid.count} x face {report.riskiest_bid.review.bid.face}
+                {report.riskiest_bid.review.bid.pure ? " pure" : ""} in round {report.riskiest_bid.round} at{" "}
+                {percent(report.riskiest_bid.review.probability)} to hold.
+              </p>
+            ) : null}
           </div>
         ) : null}
 
         {result ? (
           <div className="mt-4 grid gap-4 md:grid-cols-2">
-            <div className="rounded-xl border border-emerald-200/18 bg-emerald-500/10 p-3">
-              <DiceView dice={result.human_dice} label="Your Reveal" />
-            </div>
-            <div className="rounded-xl border border-rose-200/18 bg-rose-500/10 p-3">
-              <DiceView dice={result.ai_dice} label="AI Reveal" />
-            </div>
+            {result.dice.map((dice, index) =>
+              dice.length > 0 ? (
+                <div
+                  key={index}
+                  className={`rounded-xl border p-3 ${
+                    index === viewer ? "border-emerald-200/18 bg-emerald-500/10" : "border-rose-200/18 bg-rose-500/10"
+                  }`}
+                >
+                  <DiceView
+                    dice={dice}
+                    label={index === viewer ? "Your Reveal" : `${seats[index]?.name ?? "Opponent"} Reveal`}
+                  />
+                </div>
+              ) : null,
+            )}
           </div>
         ) : null}
 
         <div className="mt-5 flex flex-col gap-3 sm:flex-row sm:items-center sm:justify-between">
           <p className="text-sm text-slate-300">
-            Round {currentRound} of {maxRounds}
+            Round {currentRound}
+            {maxRounds !== null ? ` of ${maxRounds}` : ""}
+            {suddenDeath && !isGameOver ? " — leaders are tied, play continues." : ""}
           </p>
           {isGameOver ? (
             <button className="primary-btn" onClick={onNewGame}>
@@ -88,7 +188,7 @@ export default function ResultModal({
             </button>
           ) : (
             <button className="muted-btn" onClick={onNextRound}>
-              Start Round {Math.min(currentRound + 1, maxRounds)}
+              {suddenDeath ? "Start Sudden Death" : `Start Round ${currentRound + 1}`}
             </button>
           )}
         </div>
@@ -96,3 +196,11 @@ export default function ResultModal({
     </div>
   );
 }
+
+function percent(probability: number): string {
+  return `${Math.round(probability * 100)}%`;
+}
+
+function signed(value: number): string {
+  return `${value >= 0 ? "+" : ""}${value.toFixed(2)}`;
+}
@@ -1,22 +1,22 @@
+import type { CompletedRound, SeatView } from "../types";
+
 interface ScoreBoardProps {
   currentRound: number;
-  maxRounds: number;
-  humanWins: number;
-  aiWins: number;
+  /** null for open-ended formats such as dice loss. */
+  maxRounds: number | null;
+  seats: SeatView[];
+  rounds: CompletedRound[];
+  viewer: number;
 }
 
-export default function ScoreBoard({
-  currentRound,
-  maxRounds,
-  humanWins,
-  aiWins,
-}: ScoreBoardProps) {
-  const progress = Math.min(100, Math.max(0, ((currentRound - 1) / maxRounds) * 100));
-  const humanWinRate = (humanWins / maxRounds) * 100;
-  const aiWinRate = (aiWins / maxRounds) * 100;
-  const lead = humanWins - aiWins;
+export default function ScoreBoard({ currentRound, maxRounds, seats, rounds, viewer }: ScoreBoardProps) {
+  const roundSpan = maxRounds ?? currentRound;
+  const progress = Math.min(100, Math.max(0, ((currentRound - 1) / roundSpan) * 100));
+  const myWins = seats[viewer]?.wins ?? 0;
+  const bestRival = Math.max(0, ...seats.filter((_, index) => index !== viewer).map((seat) => seat.wins));
+  const lead = myWins - bestRival;
   const leadMessage =
-    lead === 0 ? "Dead even table." : lead > 0 ? "You have the momentum." : "AI is ahead.";
+    lead === 0 ? "Dead even table." : lead > 0 ? "You have the momentum." : "An opponent is ahead.";
 
   return (
     <section className="panel p-4 md:p-5">
@@ -24,14 +24,18 @@ export default function ScoreBoard({
         <div>
           <p className="section-label">Match Progress</p>
           <h2 className="title-font text-2xl text-amber-100">
-            Round {currentRound} / {maxRounds}
+            {maxRounds === null
+              ? `Round ${currentRound}`
+              : currentRound > maxRounds
+                ? "Sudden Death"
+                : `Round ${currentRound} / ${maxRounds}`}
           </h2>
           <p className="text-sm text-slate-300">{leadMessage}</p>
         </div>
         <div className="min-w-48 rounded-xl border border-amber-200/20 bg-slate-950/45 px-3 py-2 text-right">
           <p className="text-xs uppercase tracking-[0.18em] text-amber-100/70">Current Score</p>
           <p className="mt-1 text-lg font-semibold text-slate-100">
-            You {humanWins} : {aiWins} AI
+            {seats.map((seat) => seat.wins).join(" : ")}
           </p>
         </div>
       </div>
@@ -43,26 +47,52 @@ export default function ScoreBoard({
         />
       </div>
 
-      <div className="mt-4 grid gap-3 md:grid-cols-2">
-        <div className="rounded-xl border border-emerald-200/20 bg-emerald-500/10 p-3">
-          <div className="flex items-center justify-between text-sm">
-            <span className="font-semibold text-emerald-200">You</span>
-            <span className="text-emerald-100/90">{humanWins} wins</span>
-          </div>
-          <div className="mt-2 h-1.5 overflow-hidden rounded-full bg-slate-900/75">
-            <div className="h-full rounded-full bg-emerald-300/85" style={{ width: `${humanWinRate}%` }} />
-          </div>
+      {rounds.length > 0 ? (
+        <div className="mt-3 flex flex-wrap gap-2">
+          {rounds.map(({ result }) => (
+            <span
+              key={result.round}
+              title={`${result.last_bid.count} x face ${result.last_bid.face}${result.last_bid.pure ? " pure" : ""}, actual ${result.actual_count}`}
+              className={`rounded-full border px-2 py-0.5 text-xs ${
+                result.winner === viewer
+                  ? "border-emerald-200/25 text-emerald-100"
+                  : "border-rose-200/25 text-rose-100"
+              }`}
+            >
+              R{result.round}: {result.winner === viewer ? "You" : seats[result.winner]?.name ?? "Opponent"}
+            </span>
+          ))}
         </div>
+      ) : null}
 
-        <div className="rounded-xl border border-rose-200/20 bg-rose-500/10 p-3">
-          <div className="flex items-center justify-between text-sm">
-            <span className="font-semibold text-rose-200">AI</span>
-            <span className="text-rose-100/90">{aiWins} wins</span>
-          </div>
-          <div className="mt-2 h-1.5 overflow-hidden rounded-full bg-slate-900/75">
-            <div className="h-full rounded-full bg-rose-300/85" style={{ width: `${aiWinRate}%` }} />
-          </div>
-        </div>
+      <div className="mt-4 grid gap-3 md:grid-cols-2">
+        {seats.map((seat, index) => {
+          const isViewer = index === viewer;
+          const winRate = Math.min(100, (seat.wins / roundSpan) * 100);
+
+          return (
+            <div
+              key={index}
+              className={`rounded-xl border p-3 ${
+                isViewer ? "border-emerald-200/20 bg-emerald-500/10" : "border-rose-200/20 bg-rose-500/10"
+              }`}
+            >
+              <div className="flex items-center justify-between text-sm">
+                <span className={`font-semibold ${isViewer ? "text-emerald-200" : "text-rose-200"}`}>
+                  {isViewer ? "You" : seat.name}
+                  {seat.personality && <span className="ml-1 text-xs font-normal opacity-75">({seat.personality})</span>}
+                </span>
+                <span className={isViewer ? "text-emerald-100/90" : "text-rose-100/90"}>{seat.wins} wins</span>
+              </div>
+              <div className="mt-2 h-1.5 overflow-hidden rounded-full bg-slate-900/75">
+                <div
+                  className={`h-full rounded-full ${isViewer ? "bg-emerald-300/85" : "bg-rose-300/85"}`}
+                  style={{ width: `${winRate}%` }}
+                />
+              </div>
+            </div>
+          );
+        })}
       </div>
     </section>
   );
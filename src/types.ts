@@ -1,38 +1,193 @@
+export type MatchFormat = "FixedRounds" | "DiceLoss";
+
+export type TieBreak = "Draw" | "SuddenDeath" | "MostDice" | "SeatOrder" | "LastRoundWinner";
+
+export type OpenerRule = "Fixed" | "LoserOpens" | "WinnerOpens" | "Alternate" | "Random";
+
+export type Difficulty = "Easy" | "Normal" | "Hard" | "Expert" | "Master";
+
+export type PlayerKind = "Human" | "AI";
+
+export type Personality = "Cautious" | "Reckless" | "Mathematician" | "Bluffer";
+
+export interface PersonalityInfo {
+  personality: Personality;
+  name: string;
+  description: string;
+}
+
+export interface PlayerConfig {
+  name: string;
+  kind: PlayerKind;
+  bluff_rate?: number | null;
+  personality?: Personality | null;
+}
+
+export interface GameConfig {
+  wild_ones: boolean;
+  format: MatchFormat;
+  palifico: boolean;
+  players: PlayerConfig[];
+  dice_per_player: number;
+  die_sides: number;
+  max_rounds: number;
+  reroll_straights: boolean;
+  first_opener: number;
+  opener_rule: OpenerRule;
+  tie_break: TieBreak;
+  seed: number | null;
+  difficulty: Difficulty;
+}
+
 export interface Bid {
   count: number;
   face: number;
+  pure: boolean;
 }
 
-export type Player = "Human" | "AI";
+export type Action = { Bid: Bid } | "Challenge" | "SpotOn";
+
+export interface BidReview {
+  seat: number;
+  bid: Bid;
+  held: number;
+  bluff: boolean;
+  probability: number;
+}
 
-export type Action = { Bid: Bid } | "Challenge";
+export interface CallReview {
+  seat: number;
+  probability: number;
+  exact_probability: number;
+  expected_value: number;
+  alternative_value: number;
+}
 
 export interface RoundResult {
   round: number;
-  winner: Player;
-  loser: Player;
-  human_dice: number[];
-  ai_dice: number[];
+  winner: number;
+  loser: number;
+  dice: number[][];
+  bids: BidReview[];
+  call: CallReview;
   last_bid: Bid;
   actual_count: number;
+  wild_count: number;
+  spot_on: boolean;
+  palifico: number | null;
+  stake: number;
+  seed: number | null;
+}
+
+export interface CompletedRound {
+  opener: number;
+  bid_history: [number, Action][];
+  taunts: Taunt[];
+  result: RoundResult;
+}
+
+export interface RiskyBid {
+  round: number;
+  review: BidReview;
+}
+
+export interface PlayerReport {
+  seat: number;
+  name: string;
+  bids: number;
+  bluffs: number;
+  bluff_rate: number | null;
+  average_bid_probability: number | null;
+  calls: number;
+  correct_calls: number;
+  call_accuracy: number | null;
+  bids_called: number;
+  bids_defended: number;
+  expected_call_value: number;
+  actual_call_value: number;
+  dice_luck: number | null;
+}
+
+export interface MatchReport {
+  winner: number | null;
+  rounds: CompletedRound[];
+  players: PlayerReport[];
+  riskiest_bid: RiskyBid | null;
+}
+
+export interface RaiseOdds {
+  bid: Bid;
+  probability: number;
+}
+
+export interface Hint {
+  bid_probability: number | null;
+  exact_probability: number | null;
+  raises: RaiseOdds[];
+  recommended: Action;
+}
+
+export interface OddsQuery {
+  bid: Bid;
+  my_dice: number[];
+  opponent_dice: number[];
+  config?: GameConfig;
+  palifico?: boolean;
+}
+
+export interface Odds {
+  at_least: number;
+  exactly: number;
+}
+
+export interface PlayerProfile {
+  rounds: number;
+  bids: number;
+  bluffs: number;
+  openings: number;
+  opening_bluffs: number;
+  opening_margin_sum: number;
+  challenges: number;
+  challenge_margin_sum: number;
 }
 
 export type GamePhase =
-  | "PlayerTurn"
-  | "AITurn"
+  | { Turn: number }
   | { RoundOver: RoundResult }
-  | { GameOver: { winner: Player } };
+  | { GameOver: { winner: number | null } };
+
+export interface SeatView {
+  name: string;
+  kind: PlayerKind;
+  personality: Personality | null;
+  dice_count: number;
+  wins: number;
+}
+
+export interface Taunt {
+  seat: number;
+  action: number;
+  line: string;
+}
 
 export interface GameView {
+  seat: number;
   phase: GamePhase;
-  human_dice: number[];
-  ai_dice_count: number;
-  human_dice_count: number;
-  bid_history: [Player, Action][];
+  dice: number[];
+  seats: SeatView[];
+  bid_history: [number, Action][];
+  taunts: Taunt[];
   current_bid: Bid | null;
+  current_turn: number;
+  opener: number;
   current_round: number;
   max_rounds: number;
-  human_wins: number;
-  ai_wins: number;
   last_round_result: RoundResult | null;
+  rounds: CompletedRound[];
+  config: GameConfig;
+  palifico: number | null;
+  locked_face: number | null;
+  seed: number | null;
+  difficulty: Difficulty;
+  profile_warning: string | null;
 }